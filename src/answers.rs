@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::solution::{Answer, Part};
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Check {
+    Match,
+    Mismatch(Answer),
+    Missing,
+}
+
+// Expected answers, kept in a small subset of TOML:
+//
+//   [day01]
+//   part1 = 3457681
+//   part2 = "multi-line\nanswers are strings"
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Answers(BTreeMap<(u32, Part), Answer>);
+
+fn parse_value(value: &str) -> Result<Answer, String> {
+    let s = match value.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(s) => s,
+        None => {
+            return value
+                .parse()
+                .map(Answer::Num)
+                .map_err(|_| format!("Invalid value {}", value))
+        }
+    };
+    let mut text = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        text.push(match c {
+            '\\' => match chars.next() {
+                Some('n') => '\n',
+                Some('"') => '"',
+                Some('\\') => '\\',
+                _ => return Err(format!("Invalid escape in {}", value)),
+            },
+            '"' => return Err(format!("Unescaped quote in {}", value)),
+            _ => c,
+        });
+    }
+    Ok(Answer::Text(text))
+}
+
+// Images are kept as they are rendered.
+fn stored(answer: Answer) -> Answer {
+    match answer {
+        Answer::Image(_) => Answer::Text(answer.to_string()),
+        _ => answer,
+    }
+}
+
+impl FromStr for Answers {
+    type Err = AocError;
+
+    fn from_str(answers: &str) -> Result<Self, Self::Err> {
+        let mut map = BTreeMap::new();
+        let mut day = None;
+        for line in answers.lines().map(str::trim) {
+            let err = |token: &str, msg: String| AocError::at(answers, token, msg);
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(table) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let num = table.strip_prefix("day").and_then(|d| d.parse().ok());
+                day = Some(num.ok_or_else(|| err(table, format!("Invalid table {}", table)))?);
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err(line, format!("Expected key = value, got {}", line)))?;
+            let (key, value) = (key.trim(), value.trim());
+            let part = match key {
+                "part1" => Part::One,
+                "part2" => Part::Two,
+                key => return Err(err(key, format!("Invalid key {}", key))),
+            };
+            let day = day.ok_or_else(|| err(line, "Answer outside of a [dayNN] table".into()))?;
+            map.insert(
+                (day, part),
+                parse_value(value).map_err(|msg| err(value, msg))?,
+            );
+        }
+        Ok(Self(map))
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut last = None;
+        for ((day, part), answer) in &self.0 {
+            if last != Some(day) {
+                if last.is_some() {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[day{:02}]", day)?;
+                last = Some(day);
+            }
+            write!(f, "part{} = ", part)?;
+            match answer {
+                Answer::Num(n) => writeln!(f, "{}", n)?,
+                Answer::Text(s) => {
+                    let s = s
+                        .replace('\\', "\\\\")
+                        .replace('"', "\\\"")
+                        .replace('\n', "\\n");
+                    writeln!(f, "\"{}\"", s)?;
+                }
+                Answer::Image(_) | Answer::Empty => {
+                    unreachable!("Only numbers and text are stored")
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Answers {
+    pub fn check(&self, day: u32, part: Part, answer: &Answer) -> Check {
+        match self.0.get(&(day, part)) {
+            Some(expected) if *expected == stored(answer.clone()) => Check::Match,
+            Some(expected) => Check::Mismatch(expected.clone()),
+            None => Check::Missing,
+        }
+    }
+
+    // Days without a puzzle have nothing worth recording.
+    pub fn record(&mut self, day: u32, part: Part, answer: Answer) {
+        if answer != Answer::Empty {
+            self.0.insert((day, part), stored(answer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "[day01]\n\
+                           part1 = 3457681\n\
+                           part2 = -12\n\
+                           \n\
+                           [day08]\n\
+                           part2 = \"# #\\n \\\"\\\\\"\n";
+
+    #[test]
+    fn test_parse() {
+        let answers = ANSWERS.parse::<Answers>().unwrap();
+        assert_eq!(
+            answers.check(1, Part::One, &3457681_u64.into()),
+            Check::Match
+        );
+        assert_eq!(
+            answers.check(1, Part::Two, &12_u64.into()),
+            Check::Mismatch(Answer::Num(-12))
+        );
+        assert_eq!(
+            answers.check(8, Part::Two, &Answer::Text("# #\n \"\\".into())),
+            Check::Match
+        );
+        assert_eq!(answers.check(8, Part::One, &0_u64.into()), Check::Missing);
+        assert_eq!(answers.to_string(), ANSWERS);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            "part1 = 5".parse::<Answers>(),
+            Err(AocError::Parse {
+                line: 1,
+                column: 1,
+                msg: "Answer outside of a [dayNN] table".into()
+            })
+        );
+        assert_eq!(
+            "[day01]\n  part3 = 1".parse::<Answers>(),
+            Err(AocError::Parse {
+                line: 2,
+                column: 3,
+                msg: "Invalid key part3".into()
+            })
+        );
+        assert!("[dayone]".parse::<Answers>().is_err());
+        assert!("[day01]\npart1 = x".parse::<Answers>().is_err());
+        assert!("[day01]\npart1 = \"\\t\"".parse::<Answers>().is_err());
+        assert!("[day01]\npart1".parse::<Answers>().is_err());
+    }
+
+    #[test]
+    fn test_record() {
+        let mut answers = Answers::default();
+        answers.record(25, Part::One, 42_u64.into());
+        answers.record(25, Part::Two, Answer::Empty);
+        answers.record(3, Part::Two, "a\nb".to_string().into());
+        answers.record(8, Part::Two, Answer::Image(vec![vec![false, true]]));
+        assert_eq!(
+            answers.check(8, Part::Two, &Answer::Image(vec![vec![false, true]])),
+            Check::Match
+        );
+        assert_eq!(
+            answers.to_string(),
+            "[day03]\npart2 = \"a\\nb\"\n\n[day08]\npart2 = \" \u{2588}\"\n\n[day25]\npart1 = 42\n"
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::error::AocError;
+use crate::solution::{Day, Part};
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse => f.pad("parse"),
+            Self::Solve(Part::One) => f.pad("part1"),
+            Self::Solve(Part::Two) => f.pad("part2"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    fn new(samples: &mut [Duration]) -> Self {
+        samples.sort();
+        let runs = samples.len();
+        let mid = runs / 2;
+        let median = if runs.is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Self {
+            runs,
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / runs as u32,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Row {
+    pub day: u32,
+    pub stage: Stage,
+    pub stats: Stats,
+}
+
+// Runs `f` `runs` times (at least once), returning the last result.
+fn time<T>(
+    runs: usize,
+    mut f: impl FnMut() -> Result<T, AocError>,
+) -> Result<(T, Stats), AocError> {
+    let mut samples = vec![];
+    let mut last = None;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let res = f()?;
+        samples.push(start.elapsed());
+        last = Some(res);
+    }
+    Ok((last.unwrap(), Stats::new(&mut samples)))
+}
+
+pub fn bench_day(
+    day: &Day,
+    input: &str,
+    parts: &[Part],
+    runs: usize,
+) -> Result<Vec<Row>, AocError> {
+    let (parsed, stats) = time(runs, || day.parse(input))?;
+    let mut rows = vec![Row {
+        day: day.day,
+        stage: Stage::Parse,
+        stats,
+    }];
+    for &part in parts {
+        let (_, stats) = time(runs, || day.solve(parsed.as_ref(), part))?;
+        rows.push(Row {
+            day: day.day,
+            stage: Stage::Solve(part),
+            stats,
+        });
+    }
+    Ok(rows)
+}
+
+pub fn to_csv(rows: &[Row]) -> String {
+    let mut csv = String::from("day,stage,runs,min_ns,median_ns,mean_ns\n");
+    for row in rows {
+        csv += &format!(
+            "{},{},{},{},{},{}\n",
+            row.day,
+            row.stage,
+            row.stats.runs,
+            row.stats.min.as_nanos(),
+            row.stats.median.as_nanos(),
+            row.stats.mean.as_nanos()
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&mut ms(&[5, 1, 3]));
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(3));
+        let stats = Stats::new(&mut ms(&[4, 1, 2, 9]));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.mean, Duration::from_millis(4));
+    }
+
+    #[test]
+    fn test_bench_day() {
+        let day = Day::new::<crate::p01::Solver>(1);
+        let rows = bench_day(&day, "12\n14\n", &[Part::Two], 3).unwrap();
+        let stages = rows.iter().map(|row| row.stage).collect::<Vec<_>>();
+        assert_eq!(stages, vec![Stage::Parse, Stage::Solve(Part::Two)]);
+        assert!(rows.iter().all(|row| row.stats.runs == 3));
+        assert!(bench_day(&day, "x", &[Part::One], 3).is_err());
+
+        let csv = to_csv(&rows);
+        assert!(csv.starts_with("day,stage,runs,min_ns,median_ns,mean_ns\n1,parse,3,"));
+        assert_eq!(csv.lines().count(), 3);
+    }
+}
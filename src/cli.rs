@@ -0,0 +1,314 @@
+use std::path::PathBuf;
+
+use crate::answers;
+use crate::input::{self, STDIN};
+use crate::solution::Part;
+
+pub const USAGE: &str =
+    "Usage: aoc2019 [play | network | verify | bench | disasm] <day | all | first..=last> \
+                         [--part 1|2] [--input <path> | --input-dir <dir>] \
+                         [--answers <path>] [--record] [--runs <n>] [--results <path>] \
+                         [--format text|json] [--show-art]";
+
+pub const DEFAULT_RUNS: usize = 10;
+pub const DEFAULT_RESULTS: &str = "bench.csv";
+
+const DAYS: u32 = 25;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Command {
+    Run,
+    Play,
+    Network,
+    Verify,
+    Bench,
+    Disasm,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Args {
+    pub command: Command,
+    pub days: Vec<u32>,
+    pub parts: Vec<Part>,
+    // Overrides the input file for a single day, or `-` for stdin.
+    pub input: Option<String>,
+    pub input_dir: String,
+    pub answers: String,
+    // Overwrite the expected answers with the current ones when verifying.
+    pub record: bool,
+    pub runs: usize,
+    // Where benchmark results are written as CSV.
+    pub results: String,
+    pub format: Format,
+    // Print the art of image answers below the letters read from them.
+    pub show_art: bool,
+}
+
+fn parse_day(day: &str) -> Result<u32, String> {
+    day.parse()
+        .ok()
+        .filter(|day| (1..=DAYS).contains(day))
+        .ok_or_else(|| format!("Invalid day {}", day))
+}
+
+// A single day, `all`, or an inclusive (`3..=9`) or exclusive (`3..9`) range.
+fn parse_days(days: &str) -> Result<Vec<u32>, String> {
+    let days = if days == "all" {
+        (1..=DAYS).collect()
+    } else if let Some((first, last)) = days.split_once("..=") {
+        (parse_day(first)?..=parse_day(last)?).collect()
+    } else if let Some((first, last)) = days.split_once("..") {
+        (parse_day(first)?..parse_day(last)?).collect()
+    } else {
+        vec![parse_day(days)?]
+    };
+    if days.is_empty() {
+        return Err("Empty range of days".into());
+    }
+    Ok(days)
+}
+
+fn parse_format(format: &str) -> Result<Format, String> {
+    match format {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(format!("Invalid format {}", format)),
+    }
+}
+
+fn parse_part(part: &str) -> Result<Part, String> {
+    match part {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("Invalid part {}", part)),
+    }
+}
+
+fn missing_feature(what: &str, feature: &str) -> String {
+    format!(
+        "{} needs the {} feature; rebuild with `cargo run --release --features {} -- ...`",
+        what, feature, feature
+    )
+}
+
+impl Args {
+    pub fn input_path(&self, day: u32) -> PathBuf {
+        self.input
+            .as_ref()
+            .map_or_else(|| input::path(&self.input_dir, day), PathBuf::from)
+    }
+
+    pub fn parse<S: AsRef<str>>(args: &[S]) -> Result<Self, String> {
+        let mut command = Command::Run;
+        let mut days = None;
+        let mut parts = vec![Part::One, Part::Two];
+        let mut input = None;
+        let mut input_dir = input::DEFAULT_DIR.to_string();
+        let mut answers = answers::DEFAULT_PATH.to_string();
+        let mut record = false;
+        let mut runs = DEFAULT_RUNS;
+        let mut results = DEFAULT_RESULTS.to_string();
+        let mut format = Format::Text;
+        let mut show_art = false;
+        let mut args = args.iter().map(AsRef::as_ref);
+        while let Some(arg) = args.next() {
+            match arg {
+                "play" if days.is_none() && command == Command::Run => command = Command::Play,
+                "network" if days.is_none() && command == Command::Run => {
+                    command = Command::Network
+                }
+                "verify" if days.is_none() && command == Command::Run => command = Command::Verify,
+                "bench" if days.is_none() && command == Command::Run => command = Command::Bench,
+                "disasm" if days.is_none() && command == Command::Run => command = Command::Disasm,
+                "--part" => {
+                    let part = args.next().ok_or("Missing value for --part")?;
+                    parts = vec![parse_part(part)?];
+                }
+                "--input" => {
+                    input = Some(args.next().ok_or("Missing value for --input")?.into());
+                }
+                "--input-dir" => {
+                    input_dir = args.next().ok_or("Missing value for --input-dir")?.into();
+                }
+                "--answers" => {
+                    answers = args.next().ok_or("Missing value for --answers")?.into();
+                }
+                "--record" => record = true,
+                "--runs" => {
+                    let n = args.next().ok_or("Missing value for --runs")?;
+                    runs = n
+                        .parse()
+                        .ok()
+                        .filter(|runs| *runs > 0)
+                        .ok_or_else(|| format!("Invalid number of runs {}", n))?;
+                }
+                "--results" => {
+                    results = args.next().ok_or("Missing value for --results")?.into();
+                }
+                "--format" => {
+                    format = parse_format(args.next().ok_or("Missing value for --format")?)?;
+                }
+                "--show-art" => show_art = true,
+                _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+                _ if days.is_none() => days = Some(parse_days(arg)?),
+                _ => return Err(format!("Unexpected argument {}", arg)),
+            }
+        }
+        let days = match (days, command) {
+            (Some(days), _) => days,
+            (None, Command::Verify) | (None, Command::Bench) => (1..=DAYS).collect(),
+            (None, _) => return Err(USAGE.into()),
+        };
+        if record && command != Command::Verify {
+            return Err("--record only applies to verify".into());
+        }
+        if format == Format::Json && command != Command::Run {
+            return Err("--format json only applies to running days".into());
+        }
+        if show_art
+            && (format == Format::Json || !matches!(command, Command::Run | Command::Verify))
+        {
+            return Err("--show-art only applies to text output of run and verify".into());
+        }
+        if command == Command::Disasm && days.len() != 1 {
+            return Err("Only a single day can be disassembled".into());
+        }
+        if command == Command::Play && days != [25] {
+            return Err("Only day 25 can be played".into());
+        }
+        if command == Command::Network && days != [23] {
+            return Err("Only day 23 runs on the async network".into());
+        }
+        if input.is_some() && days.len() != 1 {
+            return Err("--input can only be given for a single day".into());
+        }
+        if command == Command::Play && input.as_deref() == Some(STDIN) {
+            return Err("The game can't be played with its input on stdin".into());
+        }
+        if command == Command::Bench && !cfg!(feature = "bench") {
+            return Err(missing_feature("bench", "bench"));
+        }
+        if command == Command::Disasm && !cfg!(feature = "intcode-debug") {
+            return Err(missing_feature("disasm", "intcode-debug"));
+        }
+        if command == Command::Network && !cfg!(feature = "async") {
+            return Err(missing_feature("network", "async"));
+        }
+        if show_art && !cfg!(feature = "viz") {
+            return Err(missing_feature("--show-art", "viz"));
+        }
+        Ok(Self {
+            command,
+            days,
+            parts,
+            input,
+            input_dir,
+            answers,
+            record,
+            runs,
+            results,
+            format,
+            show_art,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let args = Args::parse(&["7", "--part", "2"]).unwrap();
+        assert_eq!(args.command, Command::Run);
+        assert_eq!(args.days, vec![7]);
+        assert_eq!(args.parts, vec![Part::Two]);
+        assert_eq!(Args::parse(&["all"]).unwrap().days.len(), 25);
+        assert_eq!(
+            Args::parse(&["3..=9"]).unwrap().days,
+            (3..=9).collect::<Vec<_>>()
+        );
+        assert_eq!(Args::parse(&["3..5"]).unwrap().days, vec![3, 4]);
+        assert_eq!(Args::parse(&["play", "25"]).unwrap().command, Command::Play);
+        let args = Args::parse(&["3", "--input", "-"]).unwrap();
+        assert_eq!(args.input.as_deref(), Some(STDIN));
+        assert_eq!(args.input_dir, input::DEFAULT_DIR);
+        let args = Args::parse(&["all", "--input-dir", "inputs"]).unwrap();
+        assert_eq!(args.input_dir, "inputs");
+        assert_eq!(args.input_path(4), PathBuf::from("inputs/p04.txt"));
+        let args = Args::parse(&["verify", "--record"]).unwrap();
+        assert_eq!(args.command, Command::Verify);
+        assert_eq!(args.days.len(), 25);
+        assert!(args.record);
+        assert_eq!(args.answers, answers::DEFAULT_PATH);
+        let args = Args::parse(&["9"]).unwrap();
+        assert_eq!(args.runs, DEFAULT_RUNS);
+        assert_eq!(args.results, DEFAULT_RESULTS);
+        assert_eq!(args.format, Format::Text);
+        let args = Args::parse(&["1..=12", "--format", "json"]).unwrap();
+        assert_eq!(args.format, Format::Json);
+        assert!(!args.show_art);
+    }
+
+    #[test]
+    fn test_features() {
+        let bench = Args::parse(&["bench", "10", "--runs", "3"]);
+        if cfg!(feature = "bench") {
+            let args = bench.unwrap();
+            assert_eq!(args.command, Command::Bench);
+            assert_eq!(args.days, vec![10]);
+            assert_eq!(args.runs, 3);
+        } else {
+            assert_eq!(bench, Err(missing_feature("bench", "bench")));
+        }
+        let disasm = Args::parse(&["disasm", "9"]);
+        if cfg!(feature = "intcode-debug") {
+            assert_eq!(disasm.unwrap().command, Command::Disasm);
+        } else {
+            assert_eq!(disasm, Err(missing_feature("disasm", "intcode-debug")));
+        }
+        let show_art = Args::parse(&["verify", "8", "--show-art"]);
+        if cfg!(feature = "viz") {
+            assert!(show_art.unwrap().show_art);
+        } else {
+            assert_eq!(show_art, Err(missing_feature("--show-art", "viz")));
+        }
+        let network = Args::parse(&["network", "23"]);
+        if cfg!(feature = "async") {
+            assert_eq!(network.unwrap().command, Command::Network);
+        } else {
+            assert_eq!(network, Err(missing_feature("network", "async")));
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(Args::parse::<&str>(&[]), Err(USAGE.into()));
+        assert!(Args::parse(&["26"]).is_err());
+        assert!(Args::parse(&["5..5"]).is_err());
+        assert!(Args::parse(&["1", "2"]).is_err());
+        assert!(Args::parse(&["1", "--part"]).is_err());
+        assert!(Args::parse(&["1", "--part", "3"]).is_err());
+        assert!(Args::parse(&["1", "--verbose"]).is_err());
+        assert!(Args::parse(&["play", "3"]).is_err());
+        assert!(Args::parse(&["play", "25", "--input", "-"]).is_err());
+        assert!(Args::parse(&["all", "--input", "p01.txt"]).is_err());
+        assert!(Args::parse(&["1", "--input"]).is_err());
+        assert!(Args::parse(&["1", "--record"]).is_err());
+        assert!(Args::parse(&["1", "verify"]).is_err());
+        assert!(Args::parse(&["bench", "--runs", "0"]).is_err());
+        assert!(Args::parse(&["1", "--format", "yaml"]).is_err());
+        assert!(Args::parse(&["verify", "--format", "json"]).is_err());
+        assert!(Args::parse(&["8", "--format", "json", "--show-art"]).is_err());
+        assert!(Args::parse(&["bench", "--show-art"]).is_err());
+        assert!(Args::parse(&["disasm", "1..=3"]).is_err());
+        assert!(Args::parse(&["network", "22"]).is_err());
+        assert!(Args::parse(&["disasm", "9", "--format", "json"]).is_err());
+    }
+}
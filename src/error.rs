@@ -0,0 +1,186 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum AocError {
+    // Positions count from 1.
+    Parse {
+        line: usize,
+        column: usize,
+        msg: String,
+    },
+    // The input has no answer, or none that the search could find.
+    NoSolution(String),
+    // An Intcode program failed, or didn't behave as the puzzle describes.
+    Vm(String),
+    Io(String),
+}
+
+// Line and column of `offset` bytes into `text`.
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let start = before.rfind('\n').map_or(0, |idx| idx + 1);
+    (line, before[start..].chars().count() + 1)
+}
+
+// Where `part` starts in `text`, if it is a slice of it.
+fn offset(text: &str, part: &str) -> Option<usize> {
+    let offset = (part.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    Some(offset).filter(|offset| offset + part.len() <= text.len())
+}
+
+impl AocError {
+    // A parse error pointing at `token`, a slice of `text`, or at the start
+    // of `text` otherwise.
+    pub fn at(text: &str, token: &str, msg: impl Into<String>) -> Self {
+        let (line, column) = position(text, offset(text, token).unwrap_or(0));
+        Self::Parse {
+            line,
+            column,
+            msg: msg.into(),
+        }
+    }
+
+    // Moves a parse error found in `part` to its position in `text`.
+    pub fn within(self, text: &str, part: &str) -> Self {
+        match (self, offset(text, part)) {
+            (Self::Parse { line, column, msg }, Some(offset)) => {
+                let (base_line, base_column) = position(text, offset);
+                Self::Parse {
+                    line: base_line + line - 1,
+                    column: if line == 1 {
+                        base_column + column - 1
+                    } else {
+                        column
+                    },
+                    msg,
+                }
+            }
+            (err, _) => err,
+        }
+    }
+
+    pub fn no_solution() -> Self {
+        Self::NoSolution("No solution found".into())
+    }
+
+    // Distinct for each kind of error; 1 and 2 are left for failed checks and
+    // usage errors.
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::Parse { .. } => 3,
+            Self::NoSolution(_) => 4,
+            Self::Vm(_) => 5,
+            Self::Io(_) => 6,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { line, column, msg } => {
+                write!(
+                    f,
+                    "Parse error at line {}, column {}: {}",
+                    line, column, msg
+                )
+            }
+            Self::NoSolution(msg) => write!(f, "{}", msg),
+            Self::Vm(msg) => write!(f, "Intcode: {}", msg),
+            Self::Io(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl error::Error for AocError {}
+
+impl From<io::Error> for AocError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err.to_string())
+    }
+}
+
+// Parses each line of `input`, placing errors within the whole input.
+pub fn parse_lines<T: FromStr<Err = AocError>>(input: &str) -> Result<Vec<T>, AocError> {
+    input
+        .lines()
+        .map(|line| {
+            line.parse()
+                .map_err(|err: AocError| err.within(input, line))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_err(line: usize, column: usize, msg: &str) -> AocError {
+        AocError::Parse {
+            line,
+            column,
+            msg: msg.into(),
+        }
+    }
+
+    #[test]
+    fn test_at() {
+        let text = "1,2\n3,x,5\n";
+        assert_eq!(
+            AocError::at(text, &text[6..7], "Bad"),
+            parse_err(2, 3, "Bad")
+        );
+        assert_eq!(AocError::at(text, text, "Bad"), parse_err(1, 1, "Bad"));
+        assert_eq!(AocError::at(text, "x", "Bad"), parse_err(1, 1, "Bad"));
+        assert_eq!(
+            parse_err(2, 3, "Bad").to_string(),
+            "Parse error at line 2, column 3: Bad"
+        );
+    }
+
+    #[test]
+    fn test_within() {
+        let text = "ab\n  cd,ef\n";
+        let line = &text[3..10];
+        let err = AocError::at(line, &line[5..], "Bad");
+        assert_eq!(err, parse_err(1, 6, "Bad"));
+        assert_eq!(err.within(text, line), parse_err(2, 6, "Bad"));
+        let err = AocError::at(line, line, "Bad").within(text, &text[5..]);
+        assert_eq!(err, parse_err(2, 3, "Bad"));
+        assert_eq!(
+            AocError::no_solution().within(text, line),
+            AocError::no_solution()
+        );
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        struct Num;
+        impl FromStr for Num {
+            type Err = AocError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s.find('x') {
+                    Some(idx) => Err(AocError::at(s, &s[idx..], "Bad")),
+                    None => Ok(Num),
+                }
+            }
+        }
+        assert_eq!(parse_lines::<Num>("1\n2\n").map(|nums| nums.len()), Ok(2));
+        assert_eq!(
+            parse_lines::<Num>("1\n2\n 3x\n").err(),
+            Some(parse_err(3, 3, "Bad"))
+        );
+        let codes = [
+            parse_err(1, 1, "").exit_code(),
+            AocError::no_solution().exit_code(),
+            AocError::Vm("".into()).exit_code(),
+            AocError::Io("".into()).exit_code(),
+        ];
+        assert_eq!(codes, [3, 4, 5, 6]);
+    }
+}
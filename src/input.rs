@@ -0,0 +1,53 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::error::AocError;
+
+pub const DEFAULT_DIR: &str = "src/input";
+pub const STDIN: &str = "-";
+
+pub fn path(dir: &str, day: u32) -> PathBuf {
+    Path::new(dir).join(format!("p{:02}.txt", day))
+}
+
+// Reads a puzzle input, from stdin if `path` is `-`.
+pub fn read(path: &Path) -> Result<String, AocError> {
+    let mut input = String::new();
+    if path == Path::new(STDIN) {
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|err| AocError::Io(format!("Cannot read input from stdin: {}", err)))?;
+    } else {
+        input = fs::read_to_string(path).map_err(|err| {
+            AocError::Io(format!("Cannot read input {}: {}", path.display(), err))
+        })?;
+    }
+    if input.trim().is_empty() {
+        return Err(AocError::Io(format!("Input {} is empty", path.display())));
+    }
+    Ok(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        assert_eq!(path("inputs", 7), Path::new("inputs/p07.txt"));
+        let path = std::env::temp_dir().join(format!("input-{}", std::process::id()));
+        assert!(read(&path)
+            .unwrap_err()
+            .to_string()
+            .starts_with("Cannot read input"));
+        fs::write(&path, " \n").unwrap();
+        assert_eq!(
+            read(&path),
+            Err(AocError::Io(format!("Input {} is empty", path.display())))
+        );
+        fs::write(&path, "1,2,3\n").unwrap();
+        assert_eq!(read(&path), Ok("1,2,3\n".into()));
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,12 +1,26 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::io;
 use std::ops::Index;
 use std::str::FromStr;
 
+use crate::error::AocError;
+
 fn ints_to_bytes(xs: &[i64]) -> Vec<u8> {
     xs.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect()
 }
 
+pub fn ascii_to_ints(s: &str) -> Vec<i64> {
+    s.bytes().map(i64::from).collect()
+}
+
+pub fn ints_to_ascii(xs: &[i64]) -> String {
+    xs.iter()
+        .filter_map(|x| u8::try_from(*x).ok().filter(u8::is_ascii))
+        .map(char::from)
+        .collect()
+}
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 enum ParamMode {
     Position,
@@ -71,7 +85,7 @@ enum Opcode {
 use Opcode::*;
 
 impl Opcode {
-    fn new(mem: &Memory) -> Result<Self, String> {
+    fn new(mem: &Memory) -> Result<Self, AocError> {
         match mem.instr() % 100 {
             op @ 1 | op @ 2 => Ok(Arith(
                 if op == 1 { Add } else { Mul },
@@ -90,7 +104,10 @@ impl Opcode {
             )),
             9 => Ok(AdjustBase(mem.in_param(1))),
             99 => Ok(Halt),
-            op => Err(format!("Invalid opcode {}", op)),
+            op => Err(AocError::Vm(format!(
+                "Invalid opcode {} at {}",
+                op, mem.ptr
+            ))),
         }
     }
 
@@ -172,16 +189,19 @@ pub struct IntcodeExec<I, O> {
 }
 
 impl FromStr for Intcode {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(code: &str) -> Result<Self, Self::Err> {
         Ok(Self {
             code: code
                 .trim()
                 .split(',')
-                .map(str::parse)
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|_| "Invalid input")?,
+                .map(str::trim)
+                .map(|val| {
+                    val.parse()
+                        .map_err(|_| AocError::at(code, val, format!("Invalid value {}", val)))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
         })
     }
 }
@@ -210,6 +230,55 @@ impl Intcode {
     }
 }
 
+// Mnemonic and parameter count of an instruction.
+#[cfg(feature = "intcode-debug")]
+const fn mnemonic(op: i64) -> Option<(&'static str, u32)> {
+    match op % 100 {
+        1 => Some(("add", 3)),
+        2 => Some(("mul", 3)),
+        3 => Some(("in", 1)),
+        4 => Some(("out", 1)),
+        5 => Some(("jnz", 2)),
+        6 => Some(("jz", 2)),
+        7 => Some(("lt", 3)),
+        8 => Some(("eq", 3)),
+        9 => Some(("arb", 1)),
+        99 => Some(("hlt", 0)),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "intcode-debug")]
+impl Intcode {
+    // One instruction per line, read straight through from the start.
+    // Anything that doesn't decode, such as data, is shown as a raw value.
+    pub fn disassemble(&self) -> String {
+        let mut lines = vec![];
+        let mut ptr = 0;
+        while ptr < self.code.len() {
+            let op = self.code[ptr];
+            let decoded = mnemonic(op).and_then(|(name, arity)| {
+                let params = (1..=arity)
+                    .map(|param| {
+                        let val = *self.code.get(ptr + param as usize)?;
+                        Some(match (op / 10_i64.pow(param + 1)) % 10 {
+                            0 => format!("[{}]", val),
+                            1 => val.to_string(),
+                            2 => format!("[rb{:+}]", val),
+                            _ => return None,
+                        })
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((format!("{:<4}{}", name, params.join(", ")), arity as usize))
+            });
+            let (text, arity) = decoded.unwrap_or_else(|| (format!("{:<4}{}", "dat", op), 0));
+            lines.push(format!("{:>5}  {}", ptr, text.trim_end()));
+            ptr += arity + 1;
+        }
+        lines.join("\n")
+    }
+}
+
 impl<I: io::Read, O: io::Write> IntcodeExec<I, O> {
     pub fn read_from<I2: io::Read>(self, stdin: I2) -> IntcodeExec<I2, O> {
         IntcodeExec {
@@ -231,19 +300,23 @@ impl<I: io::Read, O: io::Write> IntcodeExec<I, O> {
         self.read_from(io::Cursor::new(ints_to_bytes(stdin)))
     }
 
-    pub fn run(&mut self) -> Result<Vec<i64>, String> {
+    pub fn read_ascii(self, stdin: &str) -> IntcodeExec<io::Cursor<Vec<u8>>, O> {
+        self.read_vec(&ascii_to_ints(stdin))
+    }
+
+    pub fn run(&mut self) -> Result<Vec<i64>, AocError> {
         self.collect::<Result<Vec<_>, _>>()
-            .map(|outs| outs.iter().copied().filter_map(|out| out).collect())
+            .map(|outs| outs.iter().copied().flatten().collect())
     }
 
-    pub fn run_with(&mut self, vals: &[(u64, i64)]) -> Result<Vec<i64>, String> {
+    pub fn run_with(&mut self, vals: &[(u64, i64)]) -> Result<Vec<i64>, AocError> {
         for (idx, val) in vals {
             self.mem.set(*idx, *val);
         }
         self.run()
     }
 
-    pub fn run_to_out(&mut self) -> Result<Option<i64>, String> {
+    pub fn run_to_out(&mut self) -> Result<Option<i64>, AocError> {
         self.find(|res| res.is_err() || res.as_ref().unwrap().is_some())
             .unwrap_or(Ok(None))
     }
@@ -251,15 +324,37 @@ impl<I: io::Read, O: io::Write> IntcodeExec<I, O> {
 
 impl<I: io::Read + io::Write + io::Seek, O: io::Write> IntcodeExec<I, O> {
     pub fn read_next(&mut self, stdin: &[i64]) {
-        let pos = self.stdin.seek(io::SeekFrom::Current(0)).unwrap();
+        let pos = self.stdin.stream_position().unwrap();
         self.stdin.seek(io::SeekFrom::End(0)).unwrap();
         self.stdin.write_all(&ints_to_bytes(stdin)).unwrap();
         self.stdin.seek(io::SeekFrom::Start(pos)).unwrap();
     }
+
+    pub fn input_empty(&mut self) -> bool {
+        let pos = self.stdin.stream_position().unwrap();
+        let end = self.stdin.seek(io::SeekFrom::End(0)).unwrap();
+        self.stdin.seek(io::SeekFrom::Start(pos)).unwrap();
+        pos == end
+    }
+
+    pub fn run_to_input(&mut self) -> Result<Vec<i64>, AocError> {
+        let mut outs = vec![];
+        loop {
+            if matches!(Opcode::new(&self.mem), Ok(Input(_))) && self.input_empty() {
+                return Ok(outs);
+            }
+            match self.next() {
+                Some(Ok(Some(out))) => outs.push(out),
+                Some(Ok(None)) => {}
+                Some(Err(err)) => return Err(err),
+                None => return Ok(outs),
+            }
+        }
+    }
 }
 
 impl<I: io::Read, O: io::Write> Iterator for IntcodeExec<I, O> {
-    type Item = Result<Option<i64>, String>;
+    type Item = Result<Option<i64>, AocError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let op = Opcode::new(&self.mem);
@@ -277,7 +372,7 @@ impl<I: io::Read, O: io::Write> Iterator for IntcodeExec<I, O> {
                 if let Err(err) = self
                     .stdin
                     .read_exact(&mut buf)
-                    .map_err(|_| "Invalid read".into())
+                    .map_err(|_| AocError::Vm("Invalid read".into()))
                 {
                     return Some(Err(err));
                 }
@@ -288,7 +383,7 @@ impl<I: io::Read, O: io::Write> Iterator for IntcodeExec<I, O> {
                 if let Err(err) = self
                     .stdout
                     .write(&val.to_le_bytes())
-                    .map_err(|_| "Invalid write".into())
+                    .map_err(|_| AocError::Vm("Invalid write".into()))
                 {
                     return Some(Err(err));
                 }
@@ -319,6 +414,34 @@ impl<I: io::Read, O: io::Write> Iterator for IntcodeExec<I, O> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        assert_eq!("1,2, 99\n".parse(), Ok(Intcode::from(vec![1, 2, 99])));
+        assert_eq!(
+            "1,2,\n x9".parse::<Intcode>(),
+            Err(AocError::Parse {
+                line: 2,
+                column: 2,
+                msg: "Invalid value x9".into()
+            })
+        );
+        assert!(Intcode::from(vec![42]).exec().run().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "intcode-debug")]
+    fn test_disassemble() {
+        let prog = Intcode::from(vec![1101, 100, -1, 4, 3, 7, 204, -2, 1005, 99, 5]);
+        assert_eq!(
+            prog.disassemble(),
+            "    0  add 100, -1, [4]\n    4  in  [7]\n    6  out [rb-2]\n    8  jnz [99], 5"
+        );
+        assert_eq!(
+            Intcode::from(vec![99, 42, 4]).disassemble(),
+            "    0  hlt\n    1  dat 42\n    2  dat 4"
+        );
+    }
+
     #[test]
     fn test_empty_io() {
         let mut p = Intcode::from(vec![1, 0, 0, 0, 99]).exec();
@@ -422,6 +545,31 @@ mod tests {
         assert_eq!(p.run(), Ok(code));
     }
 
+    #[test]
+    fn test_ascii() {
+        let mut p = Intcode::from(vec![3, 9, 4, 9, 3, 9, 4, 9, 99, 0])
+            .exec()
+            .read_ascii("hi")
+            .write_to(vec![]);
+        assert_eq!(p.run().map(|out| ints_to_ascii(&out)), Ok("hi".into()));
+        assert_eq!(ints_to_ascii(&[35, 10, 1000, 46]), "#\n.");
+    }
+
+    #[test]
+    fn test_run_to_input() {
+        let mut p = Intcode::from(vec![3, 9, 4, 9, 3, 9, 4, 9, 99, 0])
+            .exec()
+            .read_vec(&[])
+            .write_to(vec![]);
+        assert_eq!(p.run_to_input(), Ok(vec![]));
+        p.read_next(&[5]);
+        assert!(!p.input_empty());
+        assert_eq!(p.run_to_input(), Ok(vec![5]));
+        assert!(p.input_empty());
+        p.read_next(&[6]);
+        assert_eq!(p.run_to_input(), Ok(vec![6]));
+    }
+
     #[test]
     fn test_big_number() {
         let mut p = Intcode::from(vec![1102, 34915192, 34915192, 7, 4, 7, 99, 0])
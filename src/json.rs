@@ -0,0 +1,119 @@
+use std::fmt;
+
+use crate::solution::Answer;
+
+// Just enough JSON to report answers, written compactly.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Json {
+    Null,
+    Num(i128),
+    Float(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(&'static str, Json)>),
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Num(n) => write!(f, "{}", n),
+            Self::Float(x) => write!(f, "{:.3}", x),
+            Self::Str(s) => write_str(f, s),
+            Self::Arr(xs) => {
+                write!(f, "[")?;
+                for (idx, x) in xs.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", x)?;
+                }
+                write!(f, "]")
+            }
+            Self::Obj(fields) => {
+                write!(f, "{{")?;
+                for (idx, (key, val)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_str(f, key)?;
+                    write!(f, ":{}", val)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+// Images carry their pixel rows (1 for lit), the letters they spell if any,
+// and their art.
+impl From<&Answer> for Json {
+    fn from(answer: &Answer) -> Self {
+        match answer {
+            Answer::Num(n) => Self::Num(*n),
+            Answer::Text(s) => Self::Str(s.clone()),
+            Answer::Image(rows) => Self::Obj(vec![
+                (
+                    "rows",
+                    Self::Arr(
+                        rows.iter()
+                            .map(|row| {
+                                Self::Arr(row.iter().map(|lit| Self::Num(*lit as i128)).collect())
+                            })
+                            .collect(),
+                    ),
+                ),
+                ("text", Self::Str(answer.to_string())),
+                ("art", Self::Str(answer.art().unwrap())),
+            ]),
+            Answer::Empty => Self::Null,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let json = Json::Obj(vec![
+            ("day", Json::Num(8)),
+            ("answer", Json::Str("a \"b\"\n\\\u{1}".into())),
+            ("elapsed_ms", Json::Float(1.5)),
+            ("error", Json::Null),
+            ("xs", Json::Arr(vec![Json::Num(-1), Json::Arr(vec![])])),
+        ]);
+        assert_eq!(
+            json.to_string(),
+            r#"{"day":8,"answer":"a \"b\"\n\\\u0001","elapsed_ms":1.500,"error":null,"xs":[-1,[]]}"#
+        );
+    }
+
+    #[test]
+    fn test_answer() {
+        assert_eq!(Json::from(&Answer::Num(42)).to_string(), "42");
+        assert_eq!(Json::from(&Answer::Empty).to_string(), "null");
+        let image = Answer::Image(vec![vec![true, false]]);
+        assert_eq!(
+            Json::from(&image).to_string(),
+            "{\"rows\":[[1,0]],\"text\":\"\u{2588} \",\"art\":\"\u{2588} \"}"
+        );
+    }
+}
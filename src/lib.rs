@@ -0,0 +1,82 @@
+pub mod answers;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod cli;
+pub mod error;
+pub mod input;
+pub mod intcode;
+pub mod json;
+#[cfg(feature = "async")]
+pub mod network;
+pub mod ocr;
+pub mod p01;
+pub mod p02;
+pub mod p03;
+pub mod p04;
+pub mod p05;
+pub mod p06;
+pub mod p07;
+pub mod p08;
+pub mod p09;
+pub mod p10;
+pub mod p11;
+pub mod p12;
+pub mod p13;
+pub mod p14;
+pub mod p15;
+pub mod p16;
+pub mod p17;
+pub mod p18;
+pub mod p19;
+pub mod p20;
+pub mod p21;
+pub mod p22;
+pub mod p23;
+pub mod p24;
+pub mod p25;
+pub mod runner;
+pub mod solution;
+
+use solution::Day;
+
+// Every day's solver, in order.
+pub fn days() -> Vec<Day> {
+    vec![
+        Day::new::<p01::Solver>(1),
+        Day::new::<p02::Solver>(2),
+        Day::new::<p03::Solver>(3),
+        Day::new::<p04::Solver>(4),
+        Day::new::<p05::Solver>(5),
+        Day::new::<p06::Solver>(6),
+        Day::new::<p07::Solver>(7),
+        Day::new::<p08::Solver>(8),
+        Day::new::<p09::Solver>(9),
+        Day::new::<p10::Solver>(10),
+        Day::new::<p11::Solver>(11),
+        Day::new::<p12::Solver>(12),
+        Day::new::<p13::Solver>(13),
+        Day::new::<p14::Solver>(14),
+        Day::new::<p15::Solver>(15),
+        Day::new::<p16::Solver>(16),
+        Day::new::<p17::Solver>(17),
+        Day::new::<p18::Solver>(18),
+        Day::new::<p19::Solver>(19),
+        Day::new::<p20::Solver>(20),
+        Day::new::<p21::Solver>(21),
+        Day::new::<p22::Solver>(22),
+        Day::new::<p23::Solver>(23),
+        Day::new::<p24::Solver>(24),
+        Day::new::<p25::Solver>(25),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days() {
+        let days = days().iter().map(|day| day.day).collect::<Vec<_>>();
+        assert_eq!(days, (1..=25).collect::<Vec<_>>());
+    }
+}
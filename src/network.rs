@@ -0,0 +1,196 @@
+// Drives the day 23 network with one async task per computer instead of the
+// round loop in `p23::Network`. Every computer is a task that runs until it
+// needs a packet and then yields, and the NAT is a task of its own that runs
+// after each sweep over the computers. The executor is a plain round-robin
+// over the tasks, as nothing here waits on anything but the other tasks.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use crate::error::AocError;
+use crate::intcode::Intcode;
+use crate::p23::{Event, MAX_ROUNDS, NAT, NUM_NICS};
+
+type Task<'a, T> = Pin<Box<dyn Future<Output = Result<T, AocError>> + 'a>>;
+
+// Tasks are polled in turn whether or not they were woken.
+struct Noop;
+
+impl Wake for Noop {
+    fn wake(self: Arc<Self>) {}
+}
+
+// Ready on its second poll, so the other tasks run in between.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            Poll::Pending
+        }
+    }
+}
+
+const fn yield_now() -> YieldNow {
+    YieldNow(false)
+}
+
+// Polls the tasks in order until one of them finishes.
+fn block_on<T>(mut tasks: Vec<Task<'_, T>>) -> Result<T, AocError> {
+    let waker = Waker::from(Arc::new(Noop));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        for task in tasks.iter_mut() {
+            if let Poll::Ready(res) = task.as_mut().poll(&mut cx) {
+                return res;
+            }
+        }
+    }
+}
+
+// What the tasks share: the packets waiting for each computer, the last packet
+// sent to the NAT, and whether anything happened since the NAT last looked.
+#[derive(Default)]
+struct Bus {
+    queues: Vec<VecDeque<(i64, i64)>>,
+    nat: Option<(i64, i64)>,
+    busy: bool,
+    events: Vec<Event>,
+}
+
+impl Bus {
+    fn route(&mut self, outs: &[i64], busy: bool) -> Result<(), AocError> {
+        self.busy |= busy;
+        for packet in outs.chunks(3) {
+            let (dest, x, y) = (packet[0], packet[1], packet[2]);
+            if dest == NAT {
+                self.nat = Some((x, y));
+                self.events.push(Event::Nat(x, y));
+            } else {
+                self.queues
+                    .get_mut(dest as usize)
+                    .ok_or_else(|| AocError::Vm(format!("Invalid address {}", dest)))?
+                    .push_back((x, y));
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn nic<T>(prog: &Intcode, addr: usize, bus: &RefCell<Bus>) -> Result<T, AocError> {
+    let mut nic = prog.exec().read_vec(&[addr as i64]).write_to(vec![]);
+    loop {
+        let packets = bus.borrow_mut().queues[addr].drain(..).collect::<Vec<_>>();
+        if packets.is_empty() {
+            nic.read_next(&[-1]);
+        }
+        for (x, y) in &packets {
+            nic.read_next(&[*x, *y]);
+        }
+        let outs = nic.run_to_input()?;
+        if outs.len() % 3 != 0 {
+            return Err(AocError::Vm(format!("Incomplete packet from {}", addr)));
+        }
+
+        let busy = !packets.is_empty() || !outs.is_empty();
+        bus.borrow_mut().route(&outs, busy)?;
+        yield_now().await;
+    }
+}
+
+async fn nat<T>(bus: &RefCell<Bus>, mut f: impl FnMut(Event) -> Option<T>) -> Result<T, AocError> {
+    for _ in 0..MAX_ROUNDS {
+        // Every computer has run once since the last time round.
+        yield_now().await;
+        let mut bus = bus.borrow_mut();
+        if !bus.busy {
+            if let Some((x, y)) = bus.nat {
+                bus.queues[0].push_back((x, y));
+                bus.events.push(Event::Wake(x, y));
+            }
+        }
+        bus.busy = false;
+        let res = bus.events.drain(..).find_map(&mut f);
+        if let Some(res) = res {
+            return Ok(res);
+        }
+    }
+    Err(AocError::no_solution())
+}
+
+// Like `p23::Network::find_map`, but with every computer running as a task.
+pub fn find_map<T>(prog: &Intcode, f: impl FnMut(Event) -> Option<T>) -> Result<T, AocError> {
+    let bus = RefCell::new(Bus {
+        queues: vec![VecDeque::new(); NUM_NICS],
+        ..Bus::default()
+    });
+    let mut tasks = (0..NUM_NICS)
+        .map(|addr| Box::pin(nic(prog, addr, &bus)) as Task<'_, T>)
+        .collect::<Vec<_>>();
+    tasks.push(Box::pin(nat(&bus, f)));
+    block_on(tasks)
+}
+
+// The first packet sent to the NAT, and the first packet it wakes computer 0
+// with twice in a row.
+pub fn solve(prog: &Intcode) -> Result<(i64, i64), AocError> {
+    let first = find_map(prog, |event| match event {
+        Event::Nat(_, y) => Some(y),
+        Event::Wake(_, _) => None,
+    })?;
+    let mut last = None;
+    let repeated = find_map(prog, |event| match event {
+        Event::Wake(_, y) if last == Some(y) => Some(y),
+        Event::Wake(_, y) => {
+            last = Some(y);
+            None
+        }
+        Event::Nat(_, _) => None,
+    })?;
+    Ok((first, repeated))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p23::Network;
+
+    // Each computer sends (addr, 10 * addr + 7) to the NAT on boot and then
+    // ignores everything it receives.
+    fn prog() -> Intcode {
+        Intcode::from(vec![
+            3, 100, 104, 255, 4, 100, 1002, 100, 10, 101, 1001, 101, 7, 101, 4, 101, 3, 102, 1105,
+            1, 16,
+        ])
+    }
+
+    #[test]
+    fn test_events() {
+        let mut events = vec![];
+        let res = find_map(&prog(), |event| {
+            events.push(event);
+            Some(()).filter(|_| events.len() == NUM_NICS + 2)
+        });
+        assert_eq!(res, Ok(()));
+
+        let mut expected = vec![];
+        let mut network = Network::new(&prog());
+        while expected.len() < events.len() {
+            expected.extend(network.round().unwrap());
+        }
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(solve(&prog()), Ok((7, 497)));
+    }
+}
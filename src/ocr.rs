@@ -0,0 +1,100 @@
+// Reads the capital letters that some puzzles draw as their answer. Letters
+// are 4 pixels wide and 6 high, with a blank column between them.
+const HEIGHT: usize = 6;
+const WIDTH: usize = 4;
+
+const GLYPHS: [(char, [&str; HEIGHT]); 16] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+fn glyph(rows: &[&str; HEIGHT]) -> u32 {
+    rows.iter()
+        .flat_map(|row| row.chars())
+        .fold(0, |bits, c| bits << 1 | (c == '#') as u32)
+}
+
+// The cell starting at column `x`, padded with blank pixels on the right.
+fn cell(image: &[Vec<bool>], x: usize) -> u32 {
+    image
+        .iter()
+        .flat_map(|row| (x..x + WIDTH).map(move |x| row.get(x).copied().unwrap_or(false)))
+        .fold(0, |bits, lit| bits << 1 | lit as u32)
+}
+
+// Blank columns around the letters are ignored. Returns `None` unless every
+// letter is recognized.
+pub fn read(image: &[Vec<bool>]) -> Option<String> {
+    if image.len() != HEIGHT {
+        return None;
+    }
+    let width = image.iter().map(Vec::len).max()?;
+    let lit = |x: usize| image.iter().any(|row| row.get(x).copied().unwrap_or(false));
+    let first = (0..width).find(|&x| lit(x))?;
+    let last = (0..width).rev().find(|&x| lit(x))?;
+
+    let mut letters = String::new();
+    for x in (first..=last).step_by(WIDTH + 1) {
+        if lit(x + WIDTH) {
+            return None;
+        }
+        let bits = cell(image, x);
+        let (letter, _) = GLYPHS.iter().find(|(_, rows)| glyph(rows) == bits)?;
+        letters.push(*letter);
+    }
+    Some(letters)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(rows: &[&str]) -> Vec<Vec<bool>> {
+        rows.iter()
+            .map(|row| row.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_read() {
+        for (letter, rows) in &GLYPHS {
+            assert_eq!(read(&image(rows)), Some(letter.to_string()));
+        }
+        let hull = image(&[
+            "...##.###.....",
+            "....#.#..#....",
+            "....#.#..#....",
+            "....#.###.....",
+            ".#..#.#.#.....",
+            "..##..#..#....",
+        ]);
+        assert_eq!(read(&hull), Some("JR".into()));
+    }
+
+    #[test]
+    fn test_unknown() {
+        assert_eq!(read(&[]), None);
+        assert_eq!(
+            read(&image(&["....", "....", "....", "....", "....", "...."])),
+            None
+        );
+        assert_eq!(read(&image(&["#", "#", "#", "#", "#", "#"])), None);
+        assert_eq!(read(&image(&["####", "####"])), None);
+        let touching = image(&[".##.#", "#..##", "#..##", "#####", "#..##", "#..##"]);
+        assert_eq!(read(&touching), None);
+    }
+}
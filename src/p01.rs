@@ -1,8 +1,11 @@
-const fn calc_fuel(mass: u64) -> u64 {
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+pub const fn calc_fuel(mass: u64) -> u64 {
     (mass / 3).saturating_sub(2)
 }
 
-const fn calc_fuel_all(mass: u64) -> u64 {
+pub const fn calc_fuel_all(mass: u64) -> u64 {
     let mut tot = 0;
     let mut mass = mass;
     while mass > 0 {
@@ -20,16 +23,28 @@ fn part2(masses: &[u64]) -> u64 {
     masses.iter().copied().map(calc_fuel_all).sum()
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p01.txt");
-    let masses = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| "Invalid input")?;
-    let out1 = part1(&masses);
-    let out2 = part2(&masses);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input
+            .lines()
+            .map(|line| {
+                line.parse()
+                    .map_err(|_| AocError::at(input, line, format!("Invalid mass {}", line)))
+            })
+            .collect()
+    }
+
+    fn part1(masses: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(masses).into())
+    }
+
+    fn part2(masses: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(masses).into())
+    }
 }
 
 #[cfg(test)]
@@ -1,12 +1,14 @@
+use crate::error::AocError;
 use crate::intcode::Intcode;
+use crate::solution::{Answer, Solution};
 
-fn part1(prog: &Intcode) -> Result<i64, String> {
+fn part1(prog: &Intcode) -> Result<i64, AocError> {
     let mut exec = prog.exec();
     exec.run_with(&[(1, 12), (2, 2)])?;
     Ok(exec[0])
 }
 
-fn part2(prog: &Intcode) -> Result<i64, String> {
+fn part2(prog: &Intcode) -> Result<i64, AocError> {
     for noun in 0..99 {
         for verb in 0..99 {
             let mut exec = prog.exec();
@@ -15,13 +17,23 @@ fn part2(prog: &Intcode) -> Result<i64, String> {
             }
         }
     }
-    Err("No solution found".into())
+    Err(AocError::no_solution())
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p02.txt");
-    let prog = input.parse()?;
-    let out1 = part1(&prog)?;
-    let out2 = part2(&prog)?;
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog)?.into())
+    }
 }
@@ -1,9 +1,12 @@
 use std::collections::HashSet;
 use std::str::FromStr;
 
-type Point = (i64, i64);
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
 
-const fn dist(p: Point) -> u64 {
+pub type Point = (i64, i64);
+
+pub const fn dist(p: Point) -> u64 {
     (p.0.abs() + p.1.abs()) as u64
 }
 
@@ -28,12 +31,12 @@ impl Dir {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Wire {
+pub struct Wire {
     path: Vec<(Dir, u64)>,
 }
 
 impl FromStr for Wire {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(path: &str) -> Result<Self, Self::Err> {
         let path = path
@@ -45,9 +48,16 @@ impl FromStr for Wire {
                     Some('D') => Ok(Down),
                     Some('L') => Ok(Left),
                     Some('R') => Ok(Right),
-                    _ => Err("Invalid dir"),
+                    _ => Err(AocError::at(
+                        path,
+                        dir_amt,
+                        format!("Invalid dir {}", dir_amt),
+                    )),
                 }?;
-                let amt = dir_amt[1..].parse().map_err(|_| "Invalid amount")?;
+                let amt = &dir_amt[1..];
+                let amt = amt
+                    .parse()
+                    .map_err(|_| AocError::at(path, amt, format!("Invalid amount {}", amt)))?;
                 Ok((dir, amt))
             })
             .collect::<Result<_, Self::Err>>()?;
@@ -92,7 +102,7 @@ impl Wire {
             .collect()
     }
 
-    fn intersect(&self, other: &Self) -> HashSet<Point> {
+    pub fn intersect(&self, other: &Self) -> HashSet<Point> {
         let ps1 = self
             .segs()
             .into_iter()
@@ -106,7 +116,7 @@ impl Wire {
         ps1.intersection(&ps2).copied().collect()
     }
 
-    fn steps_to(&self, p: Point) -> Option<usize> {
+    pub fn steps_to(&self, p: Point) -> Option<usize> {
         self.segs()
             .into_iter()
             .flatten()
@@ -133,15 +143,33 @@ fn part2(wire1: &Wire, wire2: &Wire) -> u64 {
         .unwrap_or(0)
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p03.txt");
-    let wires = input
-        .lines()
-        .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()?;
-    let out1 = part1(&wires[0], &wires[1]);
-    let out2 = part2(&wires[0], &wires[1]);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (Wire, Wire);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        let mut wires = input.lines().map(|line| {
+            line.parse()
+                .map_err(|err: AocError| err.within(input, line))
+        });
+        match (wires.next(), wires.next()) {
+            (Some(wire1), Some(wire2)) => Ok((wire1?, wire2?)),
+            _ => Err(AocError::at(
+                input,
+                &input[input.len()..],
+                "Expected two wires",
+            )),
+        }
+    }
+
+    fn part1((wire1, wire2): &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(wire1, wire2).into())
+    }
+
+    fn part2((wire1, wire2): &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(wire1, wire2).into())
+    }
 }
 
 #[cfg(test)]
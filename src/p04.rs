@@ -1,3 +1,6 @@
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
 fn digits(x: u64) -> Vec<u32> {
     x.to_string()
         .chars()
@@ -5,7 +8,7 @@ fn digits(x: u64) -> Vec<u32> {
         .collect()
 }
 
-fn valid1(min: u64, max: u64, pass: u64) -> bool {
+pub fn valid1(min: u64, max: u64, pass: u64) -> bool {
     let ds = digits(pass);
     ds.len() == 6
         && min <= pass
@@ -30,7 +33,7 @@ fn two_match(digits: &[u32]) -> bool {
     found == 2
 }
 
-fn valid2(min: u64, max: u64, pass: u64) -> bool {
+pub fn valid2(min: u64, max: u64, pass: u64) -> bool {
     let ds = digits(pass);
     ds.len() == 6
         && min <= pass
@@ -39,7 +42,7 @@ fn valid2(min: u64, max: u64, pass: u64) -> bool {
         && ds.windows(2).all(|xs| xs[0] <= xs[1])
 }
 
-fn count_valid<F>(min: u64, max: u64, valid: F) -> usize
+pub fn count_valid<F>(min: u64, max: u64, valid: F) -> usize
 where
     F: Fn(u64, u64, u64) -> bool,
 {
@@ -54,17 +57,33 @@ fn part2(min: u64, max: u64) -> usize {
     count_valid(min, max, valid2)
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p04.txt");
-    let minmax = input
-        .trim()
-        .split('-')
-        .map(str::parse)
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|_| "Invalid input")?;
-    let out1 = part1(minmax[0], minmax[1]);
-    let out2 = part2(minmax[0], minmax[1]);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = (u64, u64);
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        let range = input.trim();
+        let minmax = range
+            .split('-')
+            .map(|n| {
+                n.parse()
+                    .map_err(|_| AocError::at(input, n, format!("Invalid bound {}", n)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        match minmax[..] {
+            [min, max] => Ok((min, max)),
+            _ => Err(AocError::at(input, range, "Expected a range min-max")),
+        }
+    }
+
+    fn part1(&(min, max): &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(min, max).into())
+    }
+
+    fn part2(&(min, max): &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(min, max).into())
+    }
 }
 
 #[cfg(test)]
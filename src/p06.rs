@@ -1,8 +1,11 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
 #[derive(PartialEq, Eq, Debug)]
-struct Orbits(HashMap<String, String>);
+pub struct Orbits(HashMap<String, String>);
 
 #[derive(PartialEq, Eq, Debug)]
 struct Parents<'a> {
@@ -12,7 +15,7 @@ struct Parents<'a> {
 }
 
 impl FromStr for Orbits {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(orbits: &str) -> Result<Self, Self::Err> {
         Ok(Self(
@@ -23,7 +26,11 @@ impl FromStr for Orbits {
                     if objs.len() == 2 {
                         Ok((objs[1].into(), objs[0].into()))
                     } else {
-                        Err("Invalid orbit".into())
+                        Err(AocError::at(
+                            orbits,
+                            orbit,
+                            format!("Invalid orbit {}", orbit),
+                        ))
                     }
                 })
                 .collect::<Result<_, Self::Err>>()?,
@@ -32,7 +39,7 @@ impl FromStr for Orbits {
 }
 
 impl Orbits {
-    fn depth(&self, obj: &str) -> u64 {
+    pub fn depth(&self, obj: &str) -> u64 {
         if self.0.contains_key(obj) {
             1 + self.depth(&self.0[obj])
         } else {
@@ -48,7 +55,7 @@ impl Orbits {
         }
     }
 
-    fn distance(&self, obj1: &str, obj2: &str) -> u64 {
+    pub fn distance(&self, obj1: &str, obj2: &str) -> u64 {
         let parents1 = self.parents(obj1).collect::<HashMap<_, _>>();
         self.parents(obj2)
             .filter(|(p, _)| parents1.contains_key(p))
@@ -79,12 +86,22 @@ fn part2(orbits: &Orbits) -> u64 {
     orbits.distance("YOU", "SAN") - 2
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p06.txt");
-    let orbits = input.parse()?;
-    let out1 = part1(&orbits);
-    let out2 = part2(&orbits);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Orbits;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(orbits: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(orbits).into())
+    }
+
+    fn part2(orbits: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(orbits).into())
+    }
 }
 
 #[cfg(test)]
@@ -1,18 +1,20 @@
 use itertools::Itertools;
 
+use crate::error::AocError;
 use crate::intcode::Intcode;
+use crate::solution::{Answer, Solution};
 
-fn run_amp(prog: &Intcode, phases: &[i64]) -> Result<i64, String> {
+pub fn run_amp(prog: &Intcode, phases: &[i64]) -> Result<i64, AocError> {
     phases.iter().try_fold(0, |input, phase| {
         prog.exec()
             .read_vec(&[*phase, input])
             .write_to(vec![])
             .run_to_out()
-            .and_then(|res| res.ok_or_else(|| "No return value".into()))
+            .and_then(|res| res.ok_or_else(|| AocError::Vm("No return value".into())))
     })
 }
 
-fn run_amp_feedback(prog: &Intcode, phases: &[i64]) -> Result<i64, String> {
+pub fn run_amp_feedback(prog: &Intcode, phases: &[i64]) -> Result<i64, AocError> {
     let mut amps = phases
         .iter()
         .map(|phase| prog.exec().read_vec(&[*phase]).write_to(vec![]))
@@ -30,7 +32,7 @@ fn run_amp_feedback(prog: &Intcode, phases: &[i64]) -> Result<i64, String> {
     }
 }
 
-fn part1(prog: &Intcode) -> Result<i64, String> {
+fn part1(prog: &Intcode) -> Result<i64, AocError> {
     (0..=4)
         .permutations(5)
         .map(|phases| run_amp(prog, &phases))
@@ -38,7 +40,7 @@ fn part1(prog: &Intcode) -> Result<i64, String> {
         .map(|outs| outs.into_iter().max().unwrap())
 }
 
-fn part2(prog: &Intcode) -> Result<i64, String> {
+fn part2(prog: &Intcode) -> Result<i64, AocError> {
     (5..=9)
         .permutations(5)
         .map(|phases| run_amp_feedback(prog, &phases))
@@ -46,12 +48,22 @@ fn part2(prog: &Intcode) -> Result<i64, String> {
         .map(|outs| outs.into_iter().max().unwrap())
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p07.txt");
-    let prog = input.parse()?;
-    let out1 = part1(&prog)?;
-    let out2 = part2(&prog)?;
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog)?.into())
+    }
 }
 
 #[cfg(test)]
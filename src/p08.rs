@@ -1,8 +1,10 @@
-use std::fmt;
 use std::ops::BitOr;
 
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
-enum Pixel {
+pub enum Pixel {
     Black,
     White,
     Transparent,
@@ -10,12 +12,12 @@ enum Pixel {
 use Pixel::*;
 
 impl Pixel {
-    fn new(pix: u32) -> Self {
+    pub fn new(pix: char) -> Option<Self> {
         match pix {
-            0 => Black,
-            1 => White,
-            2 => Transparent,
-            _ => unreachable!(),
+            '0' => Some(Black),
+            '1' => Some(White),
+            '2' => Some(Transparent),
+            _ => None,
         }
     }
 }
@@ -49,7 +51,7 @@ impl BitOr for Layer {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Layers {
+pub struct Layers {
     width: usize,
     height: usize,
     depth: usize,
@@ -57,30 +59,54 @@ struct Layers {
 }
 
 #[derive(PartialEq, Eq, Debug)]
-struct Image {
+pub struct Image {
     width: usize,
     height: usize,
     pixels: Layer,
 }
 
 impl Layers {
-    fn new(width: usize, height: usize, pixels: &str) -> Self {
-        Self {
+    // Every layer must be complete, and there must be at least one.
+    pub fn new(width: usize, height: usize, input: &str) -> Result<Self, AocError> {
+        let size = width * height;
+        let pixels = input.trim();
+        let pixels = pixels
+            .char_indices()
+            .map(|(idx, c)| {
+                Pixel::new(c).ok_or_else(|| {
+                    let token = &pixels[idx..idx + c.len_utf8()];
+                    AocError::at(input, token, format!("Invalid pixel {}", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if pixels.is_empty() || pixels.len() % size != 0 {
+            let rest = &input.trim()[pixels.len() / size * size..];
+            return Err(AocError::at(
+                input,
+                rest,
+                format!(
+                    "Incomplete layer of {} pixels, expected {}",
+                    rest.len(),
+                    size
+                ),
+            ));
+        }
+        Ok(Self {
             width,
             height,
-            depth: pixels.len() / (width * height),
+            depth: pixels.len() / size,
             layers: pixels
-                .trim()
-                .chars()
-                .map(|c| Pixel::new(c.to_digit(10).unwrap()))
-                .collect::<Vec<_>>()
-                .chunks(width * height)
+                .chunks(size)
                 .map(|layer| Layer(layer.chunks(width).map(|row| row.to_vec()).collect()))
                 .collect(),
-        }
+        })
     }
 
-    fn count_pixel(&self, layer: usize, pix: Pixel) -> usize {
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn count_pixel(&self, layer: usize, pix: Pixel) -> usize {
         self.layers[layer]
             .0
             .iter()
@@ -88,7 +114,7 @@ impl Layers {
             .sum()
     }
 
-    fn decode(&self) -> Image {
+    pub fn decode(&self) -> Image {
         Image {
             width: self.width,
             height: self.height,
@@ -102,42 +128,44 @@ impl Layers {
     }
 }
 
-impl fmt::Display for Image {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.pixels.0 {
-            for pix in row {
-                write!(
-                    f,
-                    "{}",
-                    match pix {
-                        White => "\u{2588}",
-                        _ => " ",
-                    }
-                )?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+impl Image {
+    // The rows of the image, true where the pixel is white.
+    pub fn white(&self) -> Vec<Vec<bool>> {
+        self.pixels
+            .0
+            .iter()
+            .map(|row| row.iter().map(|pix| *pix == White).collect())
+            .collect()
     }
 }
 
 fn part1(layers: &Layers) -> usize {
-    (0..layers.depth)
+    (0..layers.depth())
         .min_by_key(|layer| layers.count_pixel(*layer, Black))
         .map(|layer| layers.count_pixel(layer, White) * layers.count_pixel(layer, Transparent))
         .unwrap()
 }
 
-fn part2(layers: &Layers) -> String {
-    format!("{}", layers.decode())
+fn part2(layers: &Layers) -> Vec<Vec<bool>> {
+    layers.decode().white()
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p08.txt");
-    let img = Layers::new(25, 6, input);
-    let out1 = part1(&img);
-    let out2 = part2(&img);
-    Ok(format!("{}\n{}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Layers;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        Layers::new(25, 6, input)
+    }
+
+    fn part1(layers: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(layers).into())
+    }
+
+    fn part2(layers: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Image(part2(layers)))
+    }
 }
 
 #[cfg(test)]
@@ -147,8 +175,33 @@ mod tests {
     #[test]
     fn test_decode() {
         assert_eq!(
-            Layers::new(2, 2, "0222112222120000").decode().pixels,
+            Layers::new(2, 2, "0222112222120000\n")
+                .unwrap()
+                .decode()
+                .pixels,
             Layer(vec![vec![Black, White], vec![White, Black]])
         );
     }
+
+    #[test]
+    fn test_parse() {
+        let err = |column, msg: &str| AocError::Parse {
+            line: 1,
+            column,
+            msg: msg.into(),
+        };
+        assert_eq!(
+            Layers::new(2, 2, "0122x122"),
+            Err(err(5, "Invalid pixel x"))
+        );
+        assert_eq!(Layers::new(2, 2, "01229"), Err(err(5, "Invalid pixel 9")));
+        assert_eq!(
+            Layers::new(2, 2, "012201\n"),
+            Err(err(5, "Incomplete layer of 2 pixels, expected 4"))
+        );
+        assert_eq!(
+            Layers::new(2, 2, ""),
+            Err(err(1, "Incomplete layer of 0 pixels, expected 4"))
+        );
+    }
 }
@@ -1,25 +1,45 @@
+use crate::error::AocError;
 use crate::intcode::Intcode;
+use crate::solution::{Answer, Solution};
 
-fn part1(prog: &Intcode) -> Result<i64, String> {
+fn part1(prog: &Intcode) -> Result<i64, AocError> {
     prog.exec()
         .read_vec(&[1])
         .write_to(vec![])
         .run()
-        .and_then(|out| out.first().copied().ok_or_else(|| "No return value".into()))
+        .and_then(|out| {
+            out.first()
+                .copied()
+                .ok_or_else(|| AocError::Vm("No return value".into()))
+        })
 }
 
-fn part2(prog: &Intcode) -> Result<i64, String> {
+fn part2(prog: &Intcode) -> Result<i64, AocError> {
     prog.exec()
         .read_vec(&[2])
         .write_to(vec![])
         .run()
-        .and_then(|out| out.first().copied().ok_or_else(|| "No return value".into()))
+        .and_then(|out| {
+            out.first()
+                .copied()
+                .ok_or_else(|| AocError::Vm("No return value".into()))
+        })
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p09.txt");
-    let prog = input.parse()?;
-    let out1 = part1(&prog)?;
-    let out2 = part2(&prog)?;
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog)?.into())
+    }
 }
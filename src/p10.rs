@@ -1,36 +1,51 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::{Add, Sub};
 use std::str::FromStr;
 
-fn in_range(x: isize, y: isize, z: isize) -> bool {
-    let min = x.min(z);
-    let max = x.max(z);
-    (min <= y && y < max) || (min < y && y <= max)
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+fn gcd(x: isize, y: isize) -> isize {
+    if x == 0 {
+        y.abs()
+    } else {
+        gcd(y % x, x)
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
-struct Point {
-    x: isize,
-    y: isize,
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
 }
 
 impl Point {
-    fn new(x: isize, y: isize) -> Self {
+    pub fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
 
-    fn colinear(self, end: Self, other: Self) -> bool {
-        let diff1 = end - self;
-        let diff2 = other - end;
-        diff1.y * diff2.x == diff2.y * diff1.x
+    // The step from `self` towards `other` that lands on every grid point
+    // in between.
+    fn direction(self, other: Self) -> Self {
+        let diff = other - self;
+        let div = gcd(diff.x, diff.y);
+        Self::new(diff.x / div, diff.y / div)
     }
 
-    fn between(self, p1: Self, p2: Self) -> bool {
-        self.colinear(p1, p2) && (in_range(p1.x, self.x, p2.x) || in_range(p1.y, self.y, p2.y))
+    fn dist(self, other: Self) -> isize {
+        let diff = other - self;
+        diff.x.abs() + diff.y.abs()
     }
 
-    fn angle(self, _other: Self) -> f64 {
-        0.0
+    // Clockwise from straight up, in [0, 2π).
+    fn angle(self, other: Self) -> f64 {
+        let diff = other - self;
+        let angle = (diff.x as f64).atan2(-diff.y as f64);
+        if angle < 0.0 {
+            angle + 2.0 * std::f64::consts::PI
+        } else {
+            angle
+        }
     }
 }
 
@@ -57,10 +72,10 @@ impl Sub for Point {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Map(HashSet<Point>);
+pub struct Map(HashSet<Point>);
 
 impl FromStr for Map {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(map: &str) -> Result<Self, Self::Err> {
         Ok(Self(
@@ -81,39 +96,27 @@ impl FromStr for Map {
 }
 
 impl Map {
-    fn visible_from(&self, p1: Point) -> HashSet<Point> {
-        let mut visible = self.0.clone();
-        loop {
-            let mut remove = HashSet::new();
-            for p2 in &visible {
-                if *p2 == p1 {
-                    continue;
-                }
-                remove = visible
-                    .iter()
-                    .filter(|p3| p2 != *p3 && p2.between(p1, **p3))
-                    .copied()
-                    .collect();
-                if !remove.is_empty() {
-                    break;
-                }
-            }
-
-            if remove.is_empty() {
-                break;
-            } else {
-                visible = visible.difference(&remove).copied().collect();
+    // The closest asteroid in every direction, and `p1` itself.
+    pub fn visible_from(&self, p1: Point) -> HashSet<Point> {
+        let mut closest = HashMap::<Point, Point>::new();
+        for &p2 in self.0.iter().filter(|p2| **p2 != p1) {
+            let nearest = closest.entry(p1.direction(p2)).or_insert(p2);
+            if p1.dist(p2) < p1.dist(*nearest) {
+                *nearest = p2;
             }
         }
-        visible
+        closest
+            .into_values()
+            .chain(self.0.get(&p1).copied())
+            .collect()
     }
 
-    fn vaporize_from(&self, p: Point) -> Vaporize {
+    pub fn vaporize_from(&self, p: Point) -> Vaporize {
         Vaporize::new(self.clone(), p)
     }
 }
 
-struct Vaporize {
+pub struct Vaporize {
     map: Map,
     start: Point,
     visible: Vec<Point>,
@@ -135,7 +138,12 @@ impl Iterator for Vaporize {
     fn next(&mut self) -> Option<Self::Item> {
         if self.visible.is_empty() {
             let start = self.start;
-            self.visible = self.map.visible_from(start).iter().copied().collect();
+            self.visible = self
+                .map
+                .visible_from(start)
+                .into_iter()
+                .filter(|p| *p != start)
+                .collect();
             self.visible.sort_unstable_by(|p1, p2| {
                 start.angle(*p1).partial_cmp(&start.angle(*p2)).unwrap()
             });
@@ -151,28 +159,37 @@ impl Iterator for Vaporize {
     }
 }
 
-fn part1(map: &Map) -> (Point, usize) {
+fn part1(map: &Map) -> Option<(Point, usize)> {
     map.0
         .iter()
         .map(|p| (*p, map.visible_from(*p).len() - 1))
         .max_by_key(|(_, cnt)| *cnt)
-        .unwrap()
 }
 
-// TODO: incomplete
-fn part2(map: &Map, p: Point) -> isize {
-    map.vaporize_from(p)
-        .nth(199)
-        .map(|p| p.x * 100 + p.y)
-        .unwrap()
+fn part2(map: &Map, p: Point) -> Option<isize> {
+    map.vaporize_from(p).nth(199).map(|p| p.x * 100 + p.y)
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p10.txt");
-    let map = input.parse()?;
-    let (p, out1) = part1(&map);
-    let out2 = part2(&map, p);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> Result<Answer, AocError> {
+        let (_, cnt) = part1(map).ok_or_else(AocError::no_solution)?;
+        Ok(cnt.into())
+    }
+
+    fn part2(map: &Self::Input) -> Result<Answer, AocError> {
+        let (station, _) = part1(map).ok_or_else(AocError::no_solution)?;
+        part2(map, station)
+            .map(Answer::from)
+            .ok_or_else(|| AocError::NoSolution("Fewer than 200 asteroids to vaporize".into()))
+    }
 }
 
 #[cfg(test)]
@@ -200,7 +217,14 @@ mod tests {
         assert_eq!(map.visible_from(Point::new(4, 4)).len(), 8);
     }
 
-    #[ignore]
+    #[test]
+    fn test_no_solution() {
+        let map = "..\n..".parse::<Map>().unwrap();
+        assert_eq!(Solver::part1(&map), Err(AocError::no_solution()));
+        let map = ".#\n#.".parse::<Map>().unwrap();
+        assert!(matches!(Solver::part2(&map), Err(AocError::NoSolution(_))));
+    }
+
     #[test]
     fn test_vaporize() {
         let map = ".#....#####...#..\n\
@@ -215,6 +239,7 @@ mod tests {
         assert_eq!(vapor.next(), Some(Point::new(9, 0)));
         assert_eq!(vapor.next(), Some(Point::new(9, 1)));
         assert_eq!(vapor.next(), Some(Point::new(10, 0)));
+        assert_eq!(vapor.next(), Some(Point::new(9, 2)));
         assert_eq!(vapor.next(), Some(Point::new(11, 1)));
         assert_eq!(vapor.next(), Some(Point::new(12, 1)));
         assert_eq!(vapor.next(), Some(Point::new(11, 2)));
@@ -223,10 +248,9 @@ mod tests {
         assert_eq!(vapor.next(), Some(Point::new(13, 2)));
         assert_eq!(vapor.next(), Some(Point::new(14, 2)));
         assert_eq!(vapor.next(), Some(Point::new(15, 2)));
-        assert_eq!(vapor.next(), Some(Point::new(11, 3)));
+        assert_eq!(vapor.next(), Some(Point::new(12, 3)));
     }
 
-    #[ignore]
     #[test]
     fn test01() {
         let map = ".#..#\n\
@@ -236,7 +260,7 @@ mod tests {
                    ...##"
             .parse::<Map>()
             .unwrap();
-        assert_eq!(part1(&map), (Point::new(3, 4), 8));
+        assert_eq!(part1(&map), Some((Point::new(3, 4), 8)));
         let map = "......#.#.\n\
                    #..#.#....\n\
                    ..#######.\n\
@@ -249,7 +273,7 @@ mod tests {
                    .#....####"
             .parse()
             .unwrap();
-        assert_eq!(part1(&map), (Point::new(5, 8), 33));
+        assert_eq!(part1(&map), Some((Point::new(5, 8), 33)));
         let map = "#.#...#.#.\n\
                    .###....#.\n\
                    .#....#...\n\
@@ -262,7 +286,7 @@ mod tests {
                    .####.###."
             .parse()
             .unwrap();
-        assert_eq!(part1(&map), (Point::new(1, 2), 35));
+        assert_eq!(part1(&map), Some((Point::new(1, 2), 35)));
         let map = ".#..#..###\n\
                    ####.###.#\n\
                    ....###.#.\n\
@@ -275,7 +299,7 @@ mod tests {
                    .....#.#.."
             .parse()
             .unwrap();
-        assert_eq!(part1(&map), (Point::new(6, 3), 41));
+        assert_eq!(part1(&map), Some((Point::new(6, 3), 41)));
         let map = ".#..##.###...#######\n\
                    ##.############..##.\n\
                    .#.######.########.#\n\
@@ -298,6 +322,33 @@ mod tests {
                    ###.##.####.##.#..##"
             .parse()
             .unwrap();
-        assert_eq!(part1(&map), (Point::new(11, 13), 210));
+        assert_eq!(part1(&map), Some((Point::new(11, 13), 210)));
+    }
+
+    #[test]
+    fn test02() {
+        let map = ".#..##.###...#######\n\
+                   ##.############..##.\n\
+                   .#.######.########.#\n\
+                   .###.#######.####.#.\n\
+                   #####.##.#.##.###.##\n\
+                   ..#####..#.#########\n\
+                   ####################\n\
+                   #.####....###.#.#.##\n\
+                   ##.#################\n\
+                   #####.##.###..####..\n\
+                   ..######..##.#######\n\
+                   ####.##.####...##..#\n\
+                   .#####..#.######.###\n\
+                   ##...#.##########...\n\
+                   #.##########.#######\n\
+                   .####.#.###.###.#.##\n\
+                   ....##.##.###..#####\n\
+                   .#.#.###########.###\n\
+                   #.#.#.#####.####.###\n\
+                   ###.##.####.##.#..##"
+            .parse::<Map>()
+            .unwrap();
+        assert_eq!(part2(&map, Point::new(11, 13)), Some(802));
     }
 }
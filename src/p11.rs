@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::ops::Neg;
 
+use crate::error::AocError;
 use crate::intcode::Intcode;
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Turn {
@@ -39,7 +41,7 @@ impl Dir {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Color {
+pub enum Color {
     Black = 0,
     White = 1,
 }
@@ -67,7 +69,7 @@ impl Neg for Color {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct Robot {
+pub struct Robot {
     x: i64,
     y: i64,
     dir: Dir,
@@ -86,7 +88,7 @@ impl Default for Robot {
 }
 
 impl Robot {
-    fn color(&self) -> Color {
+    pub fn color(&self) -> Color {
         self.visited
             .get(&(self.x, self.y))
             .copied()
@@ -104,14 +106,19 @@ impl Robot {
         }
     }
 
-    fn run(&mut self, prog: &Intcode) -> Result<(), String> {
+    // The number of panels painted at least once.
+    pub fn painted(&self) -> usize {
+        self.visited.len()
+    }
+
+    pub fn run(&mut self, prog: &Intcode) -> Result<(), AocError> {
         let mut prog = prog.exec().read_vec(&[]).write_to(vec![]);
         loop {
             prog.read_next(&[self.color() as i64]);
             if let Some(color) = prog.run_to_out()? {
                 let turn = prog
                     .run_to_out()?
-                    .ok_or("Program didn't return a direction")?;
+                    .ok_or_else(|| AocError::Vm("Program didn't return a direction".into()))?;
                 self.step(Color::from(color), Turn::from(turn));
             } else {
                 break;
@@ -121,13 +128,13 @@ impl Robot {
     }
 }
 
-fn part1(prog: &Intcode) -> Result<usize, String> {
+fn part1(prog: &Intcode) -> Result<usize, AocError> {
     let mut robot = Robot::default();
     robot.run(prog)?;
-    Ok(robot.visited.len())
+    Ok(robot.painted())
 }
 
-fn part2(prog: &Intcode) -> Result<String, String> {
+fn part2(prog: &Intcode) -> Result<Vec<Vec<bool>>, AocError> {
     let mut robot = Robot::default();
     robot.visited.insert((0, 0), White);
     robot.run(prog)?;
@@ -139,31 +146,33 @@ fn part2(prog: &Intcode) -> Result<String, String> {
     let width = 1 + (xmax - xmin) as usize;
     let height = 1 + (ymax - ymin) as usize;
 
-    let mut grid = [" "]
-        .repeat(width * height)
-        .chunks(width)
-        .map(|row| row.to_vec())
-        .collect::<Vec<Vec<_>>>();
+    let mut grid = vec![vec![false; width]; height];
     for ((x, y), color) in robot.visited {
         if color == White {
-            grid[(y - ymin) as usize][(x - xmin) as usize] = "\u{2588}";
+            grid[(y - ymin) as usize][(x - xmin) as usize] = true;
         }
     }
 
     grid.reverse();
-    Ok(grid
-        .iter()
-        .map(|row| row.join(""))
-        .collect::<Vec<_>>()
-        .join("\n"))
+    Ok(grid)
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p11.txt");
-    let prog = input.parse()?;
-    let out1 = part1(&prog)?;
-    let out2 = part2(&prog)?;
-    Ok(format!("{}\n{}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Image(part2(prog)?))
+    }
 }
 
 #[cfg(test)]
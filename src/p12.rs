@@ -4,6 +4,9 @@ use std::iter::Sum;
 use std::ops::{Add, AddAssign};
 use std::str::FromStr;
 
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
 fn cmp(x: i64, y: i64) -> i64 {
     match x.cmp(&y) {
         Ordering::Greater => -1,
@@ -24,17 +27,17 @@ fn lcm(x: u64, y: u64) -> u64 {
     (x * y) / gcd(x, y)
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i64,
-    y: i64,
-    z: i64,
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
 }
 
-type Vector = Point;
+pub type Vector = Point;
 
 impl FromStr for Point {
-    type Err = String;
+    type Err = AocError;
 
     fn from_str(trip: &str) -> Result<Self, Self::Err> {
         let pts = trip
@@ -43,15 +46,17 @@ impl FromStr for Point {
             .map(|comp| {
                 comp.split('=')
                     .nth(1)
-                    .map(|x| x.parse::<i64>().map_err(|_| format!("Bad int {}", x)))
-                    .unwrap_or_else(|| Err(format!("Bad field {}", comp)))
+                    .map(|x| {
+                        x.parse::<i64>()
+                            .map_err(|_| AocError::at(trip, x, format!("Bad int {}", x)))
+                    })
+                    .unwrap_or_else(|| Err(AocError::at(trip, comp, format!("Bad field {}", comp))))
             })
             .collect::<Result<Vec<_>, _>>()?;
-        Ok(Self {
-            x: pts[0],
-            y: pts[1],
-            z: pts[2],
-        })
+        match pts[..] {
+            [x, y, z] => Ok(Self { x, y, z }),
+            _ => Err(AocError::at(trip, trip, "Expected x, y and z")),
+        }
     }
 }
 
@@ -65,12 +70,6 @@ impl From<(i64, i64, i64)> for Point {
     }
 }
 
-impl Default for Point {
-    fn default() -> Self {
-        Self { x: 0, y: 0, z: 0 }
-    }
-}
-
 impl Add for Point {
     type Output = Self;
 
@@ -115,13 +114,13 @@ impl Point {
 }
 
 #[derive(Debug, Clone, Copy)]
-struct Body {
-    pos: Point,
-    vel: Vector,
+pub struct Body {
+    pub pos: Point,
+    pub vel: Vector,
 }
 
 impl Body {
-    fn new(pos: Point) -> Self {
+    pub fn new(pos: Point) -> Self {
         Self {
             pos,
             vel: Point::default(),
@@ -144,12 +143,12 @@ impl Body {
         self.vel.abs_sum()
     }
 
-    fn energy(&self) -> u64 {
+    pub fn energy(&self) -> u64 {
         self.potential() * self.kinetic()
     }
 }
 
-fn step(moons: &mut [Body]) {
+pub fn step(moons: &mut [Body]) {
     let diffs = moons
         .iter()
         .map(|moon| moons.iter().map(|other| moon.cmp_pos(other)).sum())
@@ -162,13 +161,13 @@ fn step(moons: &mut [Body]) {
     }
 }
 
-fn step_until_repeat<F>(moons: &mut [Body], axis: F) -> u64
+pub fn step_until_repeat<F>(moons: &mut [Body], axis: F) -> u64
 where
     F: Fn(&Body) -> (i64, i64),
 {
     let mut seen = HashSet::<Vec<(i64, i64)>>::new();
     for cnt in 0.. {
-        let axes = moons.iter().map(|moon| axis(moon)).collect::<Vec<_>>();
+        let axes = moons.iter().map(&axis).collect::<Vec<_>>();
         if seen.contains(&axes) {
             return cnt;
         }
@@ -192,15 +191,29 @@ fn part2(mut moons: Vec<Body>) -> u64 {
     [x_cnt, y_cnt, z_cnt].iter().copied().fold(1, lcm)
 }
 
-pub fn run() -> Result<String, String> {
-    let input = include_str!("input/p12.txt");
-    let moons = input
-        .lines()
-        .map(|line| Ok(Body::new(line.parse()?)))
-        .collect::<Result<Vec<_>, String>>()?;
-    let out1 = part1(moons.clone(), 1000);
-    let out2 = part2(moons);
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Body>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input
+            .lines()
+            .map(|line| {
+                line.parse()
+                    .map(Body::new)
+                    .map_err(|err: AocError| err.within(input, line))
+            })
+            .collect()
+    }
+
+    fn part1(moons: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(moons.clone(), 1000).into())
+    }
+
+    fn part2(moons: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(moons.clone()).into())
+    }
 }
 
 #[cfg(test)]
@@ -1,3 +1,6 @@
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
 fn part1() -> u64 {
     0
 }
@@ -6,11 +9,22 @@ fn part2() -> u64 {
     0
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p13.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = ();
+
+    fn parse(_input: &str) -> Result<Self::Input, AocError> {
+        Ok(())
+    }
+
+    fn part1(_: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1().into())
+    }
+
+    fn part2(_: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2().into())
+    }
 }
 
 #[cfg(test)]
@@ -1,24 +1,507 @@
-fn part1() -> u64 {
-    0
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::Write;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+const ORE: &str = "ORE";
+const FUEL: &str = "FUEL";
+const CARGO_ORE: u64 = 1_000_000_000_000;
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum ReactionError {
+    Parse(String),
+    Duplicate(String),
+    Undefined(String),
+    Cycle(String),
+}
+
+impl fmt::Display for ReactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(line) => write!(f, "Invalid reaction {}", line),
+            Self::Duplicate(chem) => write!(f, "Multiple reactions produce {}", chem),
+            Self::Undefined(chem) => write!(f, "No reaction produces {}", chem),
+            Self::Cycle(chem) => write!(f, "Cyclic reaction involving {}", chem),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct Reaction {
+    amount: u64,
+    inputs: Vec<(String, u64)>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Reactions {
+    reactions: HashMap<String, Reaction>,
+    // Every chemical, ordered so that each one comes before all of its inputs.
+    order: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Debug, Default, Clone, Copy)]
+pub struct Production {
+    pub needed: u64,
+    pub produced: u64,
+}
+
+impl Production {
+    pub const fn waste(self) -> u64 {
+        self.produced - self.needed
+    }
 }
 
-fn part2() -> u64 {
-    0
+const fn gcd(x: u128, y: u128) -> u128 {
+    if x == 0 {
+        y
+    } else {
+        gcd(y % x, x)
+    }
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p14.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Ratio {
+    num: u128,
+    den: u128,
+}
+
+impl Ratio {
+    pub const fn new(num: u128, den: u128) -> Self {
+        let d = gcd(num, den);
+        Self {
+            num: num / d,
+            den: den / d,
+        }
+    }
+}
+
+impl Add for Ratio {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Mul for Ratio {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl From<u64> for Ratio {
+    fn from(x: u64) -> Self {
+        Self::new(x.into(), 1)
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.num, self.den)
+    }
+}
+
+impl ReactionError {
+    // Reaction errors only name what went wrong, so point at where that first
+    // appears in `input`.
+    fn locate(self, input: &str) -> AocError {
+        let subject = match &self {
+            Self::Parse(s) | Self::Duplicate(s) | Self::Undefined(s) | Self::Cycle(s) => s,
+        };
+        let token = input
+            .find(subject.as_str())
+            .map_or(input, |idx| &input[idx..idx + subject.len()]);
+        AocError::at(input, token, self.to_string())
+    }
+}
+
+fn parse_term(term: &str) -> Result<(String, u64), ReactionError> {
+    let parts = term.split_whitespace().collect::<Vec<_>>();
+    match parts[..] {
+        [amt, chem] => amt
+            .parse()
+            .ok()
+            .filter(|amt| *amt > 0)
+            .map(|amt| (chem.into(), amt))
+            .ok_or_else(|| ReactionError::Parse(term.into())),
+        _ => Err(ReactionError::Parse(term.into())),
+    }
+}
+
+fn parse_reaction(line: &str) -> Result<(String, Reaction), ReactionError> {
+    let sides = line.split("=>").collect::<Vec<_>>();
+    if sides.len() != 2 {
+        return Err(ReactionError::Parse(line.into()));
+    }
+    let (chem, amount) = parse_term(sides[1])?;
+    let inputs = sides[0]
+        .split(',')
+        .map(parse_term)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((chem, Reaction { amount, inputs }))
+}
+
+impl FromStr for Reactions {
+    type Err = ReactionError;
+
+    fn from_str(reactions: &str) -> Result<Self, Self::Err> {
+        let mut parsed = HashMap::new();
+        for line in reactions.lines().filter(|line| !line.trim().is_empty()) {
+            let (chem, reaction) = parse_reaction(line)?;
+            if chem == ORE || parsed.insert(chem.clone(), reaction).is_some() {
+                return Err(ReactionError::Duplicate(chem));
+            }
+        }
+        Self::new(parsed)
+    }
+}
+
+impl Reactions {
+    fn new(reactions: HashMap<String, Reaction>) -> Result<Self, ReactionError> {
+        if !reactions.contains_key(FUEL) {
+            return Err(ReactionError::Undefined(FUEL.into()));
+        }
+
+        let mut chems = reactions.keys().map(String::as_str).collect::<Vec<_>>();
+        chems.sort_unstable();
+        let mut done = HashMap::new();
+        let mut order = vec![];
+        for chem in chems {
+            Self::visit(&reactions, chem, &mut done, &mut order)?;
+        }
+        order.reverse();
+
+        Ok(Self { reactions, order })
+    }
+
+    // Depth-first post-order traversal from products to inputs. `done` maps
+    // each visited chemical to whether all of its inputs have been visited.
+    fn visit<'a>(
+        reactions: &'a HashMap<String, Reaction>,
+        chem: &'a str,
+        done: &mut HashMap<&'a str, bool>,
+        order: &mut Vec<String>,
+    ) -> Result<(), ReactionError> {
+        match done.get(chem) {
+            Some(true) => return Ok(()),
+            Some(false) => return Err(ReactionError::Cycle(chem.into())),
+            None => {}
+        }
+        done.insert(chem, false);
+        if let Some(reaction) = reactions.get(chem) {
+            for (input, _) in &reaction.inputs {
+                Self::visit(reactions, input, done, order)?;
+            }
+        } else if chem != ORE {
+            return Err(ReactionError::Undefined(chem.into()));
+        }
+        done.insert(chem, true);
+        order.push(chem.into());
+        Ok(())
+    }
+
+    fn produce(&self, fuel: u64) -> HashMap<&str, Production> {
+        let mut prods = HashMap::<&str, Production>::new();
+        prods.entry(FUEL).or_default().needed = fuel;
+        for chem in &self.order {
+            let prod = prods.entry(chem).or_default();
+            match self.reactions.get(chem) {
+                Some(reaction) => {
+                    let batches = prod.needed.div_ceil(reaction.amount);
+                    prod.produced = batches * reaction.amount;
+                    for (input, amt) in &reaction.inputs {
+                        prods.entry(input).or_default().needed += batches * amt;
+                    }
+                }
+                None => prod.produced = prod.needed,
+            }
+        }
+        prods
+    }
+
+    pub fn ore_for(&self, fuel: u64) -> u64 {
+        self.produce(fuel).get(ORE).map_or(0, |prod| prod.needed)
+    }
+
+    pub fn max_fuel(&self, ore: u64) -> u64 {
+        let (mut lo, mut hi) = (0, 1);
+        while self.ore_for(hi) <= ore {
+            lo = hi;
+            hi *= 2;
+        }
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if self.ore_for(mid) <= ore {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+// Analysis helpers for inspecting a reaction list outside of the puzzle itself.
+impl Reactions {
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph reactions {\n");
+        for chem in &self.order {
+            match self.reactions.get(chem) {
+                Some(reaction) => {
+                    writeln!(
+                        dot,
+                        "    \"{}\" [label=\"{} {}\"];",
+                        chem, reaction.amount, chem
+                    )
+                    .unwrap();
+                    for (input, amt) in &reaction.inputs {
+                        writeln!(
+                            dot,
+                            "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                            input, chem, amt
+                        )
+                        .unwrap();
+                    }
+                }
+                None => writeln!(dot, "    \"{}\";", chem).unwrap(),
+            }
+        }
+        dot.push('}');
+        dot
+    }
+
+    // Total production of every chemical needed for `fuel`, in reaction order.
+    pub fn production(&self, fuel: u64) -> Vec<(&str, Production)> {
+        let prods = self.produce(fuel);
+        self.order
+            .iter()
+            .map(|chem| {
+                (
+                    chem.as_str(),
+                    prods.get(chem.as_str()).copied().unwrap_or_default(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn waste(&self, fuel: u64) -> u64 {
+        self.production(fuel)
+            .iter()
+            .map(|(_, prod)| prod.waste())
+            .sum()
+    }
+
+    // The ore needed per fuel if reactions could run fractionally, i.e. in the
+    // limit of producing infinite fuel with no waste.
+    pub fn ore_per_fuel(&self) -> Ratio {
+        let mut needs = HashMap::<&str, Ratio>::new();
+        needs.insert(FUEL, 1.into());
+        for chem in &self.order {
+            let need = needs
+                .get(chem.as_str())
+                .copied()
+                .unwrap_or_else(|| 0.into());
+            if let Some(reaction) = self.reactions.get(chem) {
+                let batches = need * Ratio::new(1, reaction.amount.into());
+                for (input, amt) in &reaction.inputs {
+                    let prev = needs
+                        .get(input.as_str())
+                        .copied()
+                        .unwrap_or_else(|| 0.into());
+                    needs.insert(input, prev + batches * (*amt).into());
+                }
+            }
+        }
+        needs.get(ORE).copied().unwrap_or_else(|| 0.into())
+    }
+}
+
+fn part1(reactions: &Reactions) -> u64 {
+    reactions.ore_for(1)
+}
+
+fn part2(reactions: &Reactions) -> u64 {
+    reactions.max_fuel(CARGO_ORE)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Reactions;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input
+            .parse()
+            .map_err(|err: ReactionError| err.locate(input))
+    }
+
+    fn part1(reactions: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(reactions).into())
+    }
+
+    fn part2(reactions: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(reactions).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EX3: &str = "157 ORE => 5 NZVS\n\
+                       165 ORE => 6 DCFZ\n\
+                       44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL\n\
+                       12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ\n\
+                       179 ORE => 7 PSHF\n\
+                       177 ORE => 5 HKGWZ\n\
+                       7 DCFZ, 7 PSHF => 2 XJWVT\n\
+                       165 ORE => 2 GPVTF\n\
+                       3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT";
+
+    const EX4: &str = "2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG\n\
+                       17 NVRVD, 3 JNWZP => 8 VPVL\n\
+                       53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL\n\
+                       22 VJHF, 37 MNCFX => 5 FWMGM\n\
+                       139 ORE => 4 NVRVD\n\
+                       144 ORE => 7 JNWZP\n\
+                       5 MNCFX, 7 RFSQX, 2 FWMGM, 2 VPVL, 19 CXFTF => 3 HVMC\n\
+                       5 VJHF, 7 MNCFX, 9 VPVL, 37 CXFTF => 6 GNMV\n\
+                       145 ORE => 6 MNCFX\n\
+                       1 NVRVD => 8 CXFTF\n\
+                       1 VJHF, 6 MNCFX => 4 RFSQX\n\
+                       176 ORE => 6 VJHF";
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            "7 A => 1 B\n1 ORE => 1 FUEL".parse::<Reactions>(),
+            Err(ReactionError::Undefined("A".into()))
+        );
+        assert_eq!(
+            "1 ORE => 1 A".parse::<Reactions>(),
+            Err(ReactionError::Undefined("FUEL".into()))
+        );
+        assert_eq!(
+            "1 B => 1 A\n1 A => 1 B\n1 A => 1 FUEL".parse::<Reactions>(),
+            Err(ReactionError::Cycle("A".into()))
+        );
+        assert_eq!(
+            "1 ORE => 1 FUEL\n2 ORE => 1 FUEL".parse::<Reactions>(),
+            Err(ReactionError::Duplicate("FUEL".into()))
+        );
+        assert_eq!(
+            "1 ORE -> 1 FUEL".parse::<Reactions>(),
+            Err(ReactionError::Parse("1 ORE -> 1 FUEL".into()))
+        );
+    }
+
+    #[test]
+    fn test_leftover() {
+        let reactions = "10 ORE => 10 A\n\
+                         1 ORE => 1 B\n\
+                         7 A, 1 B => 1 C\n\
+                         7 A, 1 C => 1 D\n\
+                         7 A, 1 D => 1 E\n\
+                         7 A, 1 E => 1 FUEL"
+            .parse::<Reactions>()
+            .unwrap();
+        let prods = reactions.produce(1);
+        assert_eq!(prods["A"].needed, 28);
+        assert_eq!(prods["A"].produced, 30);
+        assert_eq!(prods["B"].produced, 1);
+    }
+
+    #[test]
+    fn test_dot() {
+        let reactions = "10 ORE => 10 A\n\
+                         7 A, 2 ORE => 1 FUEL"
+            .parse::<Reactions>()
+            .unwrap();
+        assert_eq!(
+            reactions.to_dot(),
+            "digraph reactions {\n    \
+                 \"FUEL\" [label=\"1 FUEL\"];\n    \
+                 \"A\" -> \"FUEL\" [label=\"7\"];\n    \
+                 \"ORE\" -> \"FUEL\" [label=\"2\"];\n    \
+                 \"A\" [label=\"10 A\"];\n    \
+                 \"ORE\" -> \"A\" [label=\"10\"];\n    \
+                 \"ORE\";\n\
+             }"
+        );
+    }
+
+    #[test]
+    fn test_production() {
+        let reactions = "10 ORE => 10 A\n\
+                         1 ORE => 1 B\n\
+                         7 A, 1 B => 1 C\n\
+                         7 A, 1 C => 1 D\n\
+                         7 A, 1 D => 1 E\n\
+                         7 A, 1 E => 1 FUEL"
+            .parse::<Reactions>()
+            .unwrap();
+        let prods = reactions.production(1);
+        assert_eq!(prods.first().map(|(chem, _)| *chem), Some("FUEL"));
+        assert_eq!(prods.last().map(|(chem, _)| *chem), Some("ORE"));
+        assert_eq!(reactions.waste(1), 2);
+        assert_eq!(reactions.waste(10), 0);
+        assert_eq!(reactions.ore_per_fuel(), Ratio::new(29, 1));
+    }
+
+    #[test]
+    fn test_ore_per_fuel() {
+        let reactions = "9 ORE => 2 A\n\
+                         8 ORE => 3 B\n\
+                         7 ORE => 5 C\n\
+                         3 A, 4 B => 1 AB\n\
+                         5 B, 7 C => 1 BC\n\
+                         4 C, 1 A => 1 CA\n\
+                         2 AB, 3 BC, 4 CA => 1 FUEL"
+            .parse::<Reactions>()
+            .unwrap();
+        assert_eq!(reactions.ore_per_fuel().to_string(), "2372/15");
+        let reactions = EX3.parse::<Reactions>().unwrap();
+        let ratio = reactions.ore_per_fuel();
+        assert_eq!(
+            u128::from(part2(&reactions)),
+            u128::from(CARGO_ORE) * ratio.den / ratio.num
+        );
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let reactions = "10 ORE => 10 A\n\
+                         1 ORE => 1 B\n\
+                         7 A, 1 B => 1 C\n\
+                         7 A, 1 C => 1 D\n\
+                         7 A, 1 D => 1 E\n\
+                         7 A, 1 E => 1 FUEL"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&reactions), 31);
+        let reactions = "9 ORE => 2 A\n\
+                         8 ORE => 3 B\n\
+                         7 ORE => 5 C\n\
+                         3 A, 4 B => 1 AB\n\
+                         5 B, 7 C => 1 BC\n\
+                         4 C, 1 A => 1 CA\n\
+                         2 AB, 3 BC, 4 CA => 1 FUEL"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&reactions), 165);
+        assert_eq!(part1(&EX3.parse().unwrap()), 13312);
+        assert_eq!(part1(&EX4.parse().unwrap()), 180697);
+    }
+
+    #[test]
+    fn test02() {
+        assert_eq!(part2(&EX3.parse().unwrap()), 82892753);
+        assert_eq!(part2(&EX4.parse().unwrap()), 5586022);
     }
 }
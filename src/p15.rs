@@ -1,24 +1,293 @@
-fn part1() -> u64 {
-    0
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io;
+
+use crate::error::AocError;
+use crate::intcode::{Intcode, IntcodeExec};
+use crate::solution::{Answer, Solution};
+
+pub type Pos = (i64, i64);
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Dir {
+    North = 1,
+    South = 2,
+    West = 3,
+    East = 4,
 }
 
-fn part2() -> u64 {
-    0
+impl Dir {
+    pub const ALL: [Self; 4] = [Self::North, Self::South, Self::West, Self::East];
+
+    pub const fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+
+    pub const fn go(self, (x, y): Pos) -> Pos {
+        match self {
+            Self::North => (x, y - 1),
+            Self::South => (x, y + 1),
+            Self::West => (x - 1, y),
+            Self::East => (x + 1, y),
+        }
+    }
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p15.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Tile {
+    Wall,
+    Open,
+    Oxygen,
+}
+
+impl Tile {
+    fn new(status: i64) -> Result<Self, AocError> {
+        match status {
+            0 => Ok(Self::Wall),
+            1 => Ok(Self::Open),
+            2 => Ok(Self::Oxygen),
+            _ => Err(AocError::Vm(format!("Invalid status {}", status))),
+        }
+    }
+}
+
+pub trait Droid {
+    fn go(&mut self, dir: Dir) -> Result<Tile, AocError>;
+}
+
+impl<O: io::Write> Droid for IntcodeExec<io::Cursor<Vec<u8>>, O> {
+    fn go(&mut self, dir: Dir) -> Result<Tile, AocError> {
+        self.read_next(&[dir as i64]);
+        Tile::new(
+            self.run_to_out()?
+                .ok_or_else(|| AocError::Vm("Droid stopped responding".into()))?,
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Map {
+    tiles: HashMap<Pos, Tile>,
+}
+
+impl Map {
+    pub const START: Pos = (0, 0);
+
+    // `None` for tiles the droid never saw.
+    pub fn tile(&self, pos: Pos) -> Option<Tile> {
+        self.tiles.get(&pos).copied()
+    }
+
+    pub fn explore<D: Droid>(droid: &mut D) -> Result<Self, AocError> {
+        let mut map = Self {
+            tiles: HashMap::new(),
+        };
+        map.tiles.insert(Self::START, Tile::Open);
+        map.explore_from(droid, Self::START)?;
+        Ok(map)
+    }
+
+    // Depth-first search that returns the droid to `pos` after visiting
+    // every unexplored neighbour.
+    fn explore_from<D: Droid>(&mut self, droid: &mut D, pos: Pos) -> Result<(), AocError> {
+        for dir in Dir::ALL {
+            let next = dir.go(pos);
+            if self.tiles.contains_key(&next) {
+                continue;
+            }
+            let tile = droid.go(dir)?;
+            self.tiles.insert(next, tile);
+            if tile != Tile::Wall {
+                self.explore_from(droid, next)?;
+                if droid.go(dir.reverse())? == Tile::Wall {
+                    return Err(AocError::Vm("Droid failed to backtrack".into()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn oxygen(&self) -> Option<Pos> {
+        self.tiles
+            .iter()
+            .find(|(_, tile)| **tile == Tile::Oxygen)
+            .map(|(pos, _)| *pos)
+    }
+
+    pub fn distances(&self, from: Pos) -> HashMap<Pos, u64> {
+        let mut dists = HashMap::new();
+        let mut todo = VecDeque::new();
+        dists.insert(from, 0);
+        todo.push_back(from);
+        while let Some(pos) = todo.pop_front() {
+            let dist = dists[&pos];
+            for dir in Dir::ALL {
+                let next = dir.go(pos);
+                let open = self
+                    .tiles
+                    .get(&next)
+                    .is_some_and(|tile| *tile != Tile::Wall);
+                if open && !dists.contains_key(&next) {
+                    dists.insert(next, dist + 1);
+                    todo.push_back(next);
+                }
+            }
+        }
+        dists
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let xmin = self.tiles.keys().map(|(x, _)| *x).min().unwrap_or(0);
+        let xmax = self.tiles.keys().map(|(x, _)| *x).max().unwrap_or(0);
+        let ymin = self.tiles.keys().map(|(_, y)| *y).min().unwrap_or(0);
+        let ymax = self.tiles.keys().map(|(_, y)| *y).max().unwrap_or(0);
+        for y in ymin..=ymax {
+            for x in xmin..=xmax {
+                let c = match self.tiles.get(&(x, y)) {
+                    _ if (x, y) == Self::START => 'D',
+                    Some(Tile::Wall) => '#',
+                    Some(Tile::Open) => '.',
+                    Some(Tile::Oxygen) => 'O',
+                    None => ' ',
+                };
+                write!(f, "{}", c)?;
+            }
+            if y != ymax {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn part1(map: &Map) -> Result<u64, AocError> {
+    let oxygen = map
+        .oxygen()
+        .ok_or_else(|| AocError::NoSolution("No oxygen system found".into()))?;
+    map.distances(Map::START)
+        .get(&oxygen)
+        .copied()
+        .ok_or_else(|| AocError::NoSolution("Oxygen system unreachable".into()))
+}
+
+fn part2(map: &Map) -> Result<u64, AocError> {
+    let oxygen = map
+        .oxygen()
+        .ok_or_else(|| AocError::NoSolution("No oxygen system found".into()))?;
+    Ok(map.distances(oxygen).values().copied().max().unwrap_or(0))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        let map = Map::explore(&mut prog.exec().read_vec(&[]).write_to(vec![]))?;
+        Ok(part1(&map)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        let map = Map::explore(&mut prog.exec().read_vec(&[]).write_to(vec![]))?;
+        Ok(part2(&map)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // A droid that moves around a known map given as text, with the droid's
+    // starting position marked by `D`.
+    struct MapDroid {
+        pos: Pos,
+        tiles: HashMap<Pos, Tile>,
+    }
+
+    impl MapDroid {
+        fn new(map: &str) -> Self {
+            let mut pos = (0, 0);
+            let mut tiles = HashMap::new();
+            for (y, line) in map.lines().enumerate() {
+                for (x, c) in line.chars().enumerate() {
+                    let p = (x as i64, y as i64);
+                    match c {
+                        '.' => tiles.insert(p, Tile::Open),
+                        'O' => tiles.insert(p, Tile::Oxygen),
+                        'D' => {
+                            pos = p;
+                            tiles.insert(p, Tile::Open)
+                        }
+                        _ => tiles.insert(p, Tile::Wall),
+                    };
+                }
+            }
+            Self { pos, tiles }
+        }
+    }
+
+    impl Droid for MapDroid {
+        fn go(&mut self, dir: Dir) -> Result<Tile, AocError> {
+            let next = dir.go(self.pos);
+            let tile = self.tiles.get(&next).copied().unwrap_or(Tile::Wall);
+            if tile != Tile::Wall {
+                self.pos = next;
+            }
+            Ok(tile)
+        }
+    }
+
+    #[test]
+    fn test_explore() {
+        let mut droid = MapDroid::new(
+            " ##   \n\
+             #..## \n\
+             #D#..#\n\
+             #.O.# \n\
+             \x20###  ",
+        );
+        let map = Map::explore(&mut droid).unwrap();
+        assert_eq!(droid.pos, (1, 2));
+        assert_eq!(
+            map.to_string(),
+            " ##   \n\
+             #..## \n\
+             #D#..#\n\
+             #.O.# \n\
+             \x20###  "
+        );
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let map = Map::explore(&mut MapDroid::new("#####\n#D.O#\n#####")).unwrap();
+        assert_eq!(part1(&map), Ok(2));
+        let map = Map::explore(&mut MapDroid::new("#####\n#D..#\n#####")).unwrap();
+        assert!(part1(&map).is_err());
+    }
+
+    #[test]
+    fn test02() {
+        let map = Map::explore(&mut MapDroid::new(
+            " ##   \n\
+             #..## \n\
+             #D#..#\n\
+             #.O.# \n\
+             \x20###  ",
+        ))
+        .unwrap();
+        assert_eq!(part1(&map), Ok(2));
+        assert_eq!(part2(&map), Ok(4));
     }
 }
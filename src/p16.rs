@@ -1,24 +1,160 @@
-fn part1() -> u64 {
-    0
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+const PHASES: usize = 100;
+const REPEAT: usize = 10_000;
+const MSG_LEN: usize = 8;
+const OFFSET_LEN: usize = 7;
+
+fn to_number(digits: &[u8]) -> u64 {
+    digits.iter().fold(0, |n, d| n * 10 + u64::from(*d))
+}
+
+// Output digit `i` is the dot product of the signal with the pattern
+// 0, 1, 0, -1 where each element is repeated `i + 1` times and the first
+// element is skipped. Using prefix sums each run of 1s or -1s is a single
+// subtraction, so a phase is O(n log n) rather than O(n^2).
+pub fn phase(signal: &[u8]) -> Vec<u8> {
+    let mut sums = vec![0_i64; signal.len() + 1];
+    for (i, d) in signal.iter().enumerate() {
+        sums[i + 1] = sums[i] + i64::from(*d);
+    }
+    let range_sum = |start: usize, len: usize| {
+        let end = (start + len).min(signal.len());
+        sums[end] - sums[start]
+    };
+
+    (0..signal.len())
+        .map(|i| {
+            let period = i + 1;
+            let mut total = 0;
+            let mut start = i;
+            while start < signal.len() {
+                total += range_sum(start, period);
+                if start + 2 * period < signal.len() {
+                    total -= range_sum(start + 2 * period, period);
+                }
+                start += 4 * period;
+            }
+            (total.abs() % 10) as u8
+        })
+        .collect()
+}
+
+// In the second half of the signal the pattern is 0 before the digit and 1
+// from it onward, so each output digit is just the sum of the suffix.
+pub fn phase_suffix(signal: &mut [u8]) {
+    let mut sum = 0;
+    for d in signal.iter_mut().rev() {
+        sum = (sum + *d) % 10;
+        *d = sum;
+    }
 }
 
-fn part2() -> u64 {
-    0
+pub fn fft(signal: &[u8], phases: usize) -> Vec<u8> {
+    (0..phases).fold(signal.to_vec(), |signal, _| phase(&signal))
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p16.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+fn part1(signal: &[u8]) -> u64 {
+    to_number(&fft(signal, PHASES)[..MSG_LEN])
+}
+
+fn part2(signal: &[u8]) -> Result<u64, AocError> {
+    let offset = to_number(&signal[..OFFSET_LEN]) as usize;
+    let len = signal.len() * REPEAT;
+    if offset < len / 2 {
+        return Err(AocError::NoSolution(format!(
+            "Message offset {} is in the first half of the {} digit signal",
+            offset, len
+        )));
+    }
+    if offset + MSG_LEN > len {
+        return Err(AocError::NoSolution(format!(
+            "Message offset {} is past the end of the signal",
+            offset
+        )));
+    }
+
+    let mut suffix = signal
+        .iter()
+        .copied()
+        .cycle()
+        .skip(offset % signal.len())
+        .take(len - offset)
+        .collect::<Vec<_>>();
+    for _ in 0..PHASES {
+        phase_suffix(&mut suffix);
+    }
+    Ok(to_number(&suffix[..MSG_LEN]))
+}
+
+fn parse(signal: &str) -> Result<Vec<u8>, AocError> {
+    let trimmed = signal.trim();
+    let digits = trimmed
+        .char_indices()
+        .map(|(idx, c)| {
+            c.to_digit(10).map(|d| d as u8).ok_or_else(|| {
+                let digit = &trimmed[idx..idx + c.len_utf8()];
+                AocError::at(signal, digit, format!("Invalid digit {}", c))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() < MSG_LEN {
+        return Err(AocError::at(signal, trimmed, "Signal too short"));
+    }
+    Ok(digits)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        parse(input)
+    }
+
+    fn part1(signal: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(signal).into())
+    }
+
+    fn part2(signal: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(signal)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_phase() {
+        let signal = parse("12345678").unwrap();
+        assert_eq!(fft(&signal, 1), parse("48226158").unwrap());
+        assert_eq!(fft(&signal, 2), parse("34040438").unwrap());
+        assert_eq!(fft(&signal, 3), parse("03415518").unwrap());
+        assert_eq!(fft(&signal, 4), parse("01029498").unwrap());
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let signal = parse("80871224585914546619083218645595").unwrap();
+        assert_eq!(part1(&signal), 24176176);
+        let signal = parse("19617804207202209144916044189917").unwrap();
+        assert_eq!(part1(&signal), 73745418);
+        let signal = parse("69317163492948606335995924319873").unwrap();
+        assert_eq!(part1(&signal), 52432133);
+    }
+
+    #[test]
+    fn test02() {
+        let signal = parse("03036732577212944063491565474664").unwrap();
+        assert_eq!(part2(&signal), Ok(84462026));
+        let signal = parse("02935109699940807407585447034323").unwrap();
+        assert_eq!(part2(&signal), Ok(78725270));
+        let signal = parse("03081770884921959731165446850517").unwrap();
+        assert_eq!(part2(&signal), Ok(53553731));
+        let signal = parse("80871224585914546619083218645595").unwrap();
+        assert!(part2(&signal).is_err());
     }
 }
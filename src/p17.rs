@@ -1,24 +1,330 @@
-fn part1() -> u64 {
-    0
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::intcode::{ints_to_ascii, Intcode};
+use crate::solution::{Answer, Solution};
+
+const MAX_ROUTINE_LEN: usize = 20;
+const NUM_FUNCS: usize = 3;
+
+pub type Pos = (i64, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Dir {
+    fn new(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Self::Up),
+            'v' => Some(Self::Down),
+            '<' => Some(Self::Left),
+            '>' => Some(Self::Right),
+            _ => None,
+        }
+    }
+
+    const fn turn(self, t: Turn) -> Self {
+        match (self, t) {
+            (Self::Up, Turn::Left) | (Self::Down, Turn::Right) => Self::Left,
+            (Self::Down, Turn::Left) | (Self::Up, Turn::Right) => Self::Right,
+            (Self::Left, Turn::Left) | (Self::Right, Turn::Right) => Self::Down,
+            (Self::Right, Turn::Left) | (Self::Left, Turn::Right) => Self::Up,
+        }
+    }
+
+    const fn go(self, (x, y): Pos) -> Pos {
+        match self {
+            Self::Up => (x, y - 1),
+            Self::Down => (x, y + 1),
+            Self::Left => (x - 1, y),
+            Self::Right => (x + 1, y),
+        }
+    }
+}
+
+// A turn followed by some number of steps forward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment(pub Turn, pub u64);
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let turn = match self.0 {
+            Turn::Left => 'L',
+            Turn::Right => 'R',
+        };
+        write!(f, "{},{}", turn, self.1)
+    }
+}
+
+fn join<T: ToString>(xs: &[T]) -> String {
+    xs.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scaffold {
+    tiles: HashSet<Pos>,
+    robot: Pos,
+    dir: Dir,
+}
+
+impl FromStr for Scaffold {
+    type Err = AocError;
+
+    fn from_str(view: &str) -> Result<Self, Self::Err> {
+        let mut tiles = HashSet::new();
+        let mut robot = None;
+        for (y, line) in view.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let pos = (x as i64, y as i64);
+                match c {
+                    '#' => {
+                        tiles.insert(pos);
+                    }
+                    '.' => {}
+                    _ => {
+                        let dir = Dir::new(c).ok_or_else(|| {
+                            AocError::Vm(format!("Invalid tile {} at {:?} in camera view", c, pos))
+                        })?;
+                        robot = Some((pos, dir));
+                        tiles.insert(pos);
+                    }
+                }
+            }
+        }
+        let (robot, dir) = robot.ok_or_else(|| AocError::Vm("No robot found".into()))?;
+        Ok(Self { tiles, robot, dir })
+    }
+}
+
+impl Scaffold {
+    pub fn intersections(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.tiles.iter().copied().filter(move |pos| {
+            [Dir::Up, Dir::Down, Dir::Left, Dir::Right]
+                .iter()
+                .all(|dir| self.tiles.contains(&dir.go(*pos)))
+        })
+    }
+
+    // Follow the scaffold by going straight as long as possible and only
+    // turning at corners, which passes through every intersection.
+    pub fn path(&self) -> Vec<Segment> {
+        let mut path = vec![];
+        let mut pos = self.robot;
+        let mut dir = self.dir;
+        while let Some(turn) = [Turn::Left, Turn::Right]
+            .iter()
+            .copied()
+            .find(|t| self.tiles.contains(&dir.turn(*t).go(pos)))
+        {
+            dir = dir.turn(turn);
+            let mut steps = 0;
+            while self.tiles.contains(&dir.go(pos)) {
+                pos = dir.go(pos);
+                steps += 1;
+            }
+            path.push(Segment(turn, steps));
+        }
+        path
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Routines {
+    main: Vec<usize>,
+    funcs: Vec<Vec<Segment>>,
 }
 
-fn part2() -> u64 {
-    0
+impl Routines {
+    pub fn compress(path: &[Segment]) -> Option<Self> {
+        let mut routines = Self {
+            main: vec![],
+            funcs: vec![],
+        };
+        if routines.compress_from(path) {
+            Some(routines)
+        } else {
+            None
+        }
+    }
+
+    // Cover `path` with calls to existing functions, or define a new one from
+    // a prefix of the remaining path, backtracking when a choice fails.
+    fn compress_from(&mut self, path: &[Segment]) -> bool {
+        if path.is_empty() {
+            return true;
+        }
+        if self.main_routine().len() + 2 > MAX_ROUTINE_LEN {
+            return false;
+        }
+
+        for func in 0..self.funcs.len() {
+            if path.starts_with(&self.funcs[func]) {
+                self.main.push(func);
+                if self.compress_from(&path[self.funcs[func].len()..]) {
+                    return true;
+                }
+                self.main.pop();
+            }
+        }
+
+        if self.funcs.len() < NUM_FUNCS {
+            let func = self.funcs.len();
+            for len in (1..=path.len()).rev() {
+                if join(&path[..len]).len() > MAX_ROUTINE_LEN {
+                    continue;
+                }
+                self.funcs.push(path[..len].to_vec());
+                self.main.push(func);
+                if self.compress_from(&path[len..]) {
+                    return true;
+                }
+                self.main.pop();
+                self.funcs.pop();
+            }
+        }
+        false
+    }
+
+    pub fn main_routine(&self) -> String {
+        self.main
+            .iter()
+            .map(|func| ((b'A' + *func as u8) as char).to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl fmt::Display for Routines {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.main_routine())?;
+        for func in 0..NUM_FUNCS {
+            writeln!(
+                f,
+                "{}",
+                self.funcs.get(func).map_or("".into(), |seg| join(seg))
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub fn camera(prog: &Intcode) -> Result<Scaffold, AocError> {
+    ints_to_ascii(&prog.exec().write_to(vec![]).run()?).parse()
+}
+
+fn part1(scaffold: &Scaffold) -> i64 {
+    scaffold.intersections().map(|(x, y)| x * y).sum()
+}
+
+fn part2(prog: &Intcode, scaffold: &Scaffold) -> Result<i64, AocError> {
+    let routines = Routines::compress(&scaffold.path())
+        .ok_or_else(|| AocError::NoSolution("Failed to compress path".into()))?;
+    let mut exec = prog
+        .exec()
+        .read_ascii(&format!("{}n\n", routines))
+        .write_to(vec![]);
+    exec.run_with(&[(0, 2)])?
+        .last()
+        .copied()
+        .filter(|dust| *dust > 127)
+        .ok_or_else(|| AocError::Vm("No dust reported".into()))
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p17.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(&camera(prog)?).into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog, &camera(prog)?)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EX2: &str = "#######...#####\n\
+                       #.....#...#...#\n\
+                       #.....#...#...#\n\
+                       ......#...#...#\n\
+                       ......#...###.#\n\
+                       ......#.....#.#\n\
+                       ^########...#.#\n\
+                       ......#.#...#.#\n\
+                       ......#########\n\
+                       ........#...#..\n\
+                       ....#########..\n\
+                       ....#...#......\n\
+                       ....#...#......\n\
+                       ....#...#......\n\
+                       ....#####......";
+
+    fn expand(routines: &Routines) -> Vec<Segment> {
+        routines
+            .main
+            .iter()
+            .flat_map(|func| routines.funcs[*func].iter().copied())
+            .collect()
+    }
+
+    #[test]
+    fn test_path() {
+        let scaffold = EX2.parse::<Scaffold>().unwrap();
+        assert_eq!(
+            join(&scaffold.path()),
+            "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2"
+        );
+    }
+
+    #[test]
+    fn test_compress() {
+        let path = EX2.parse::<Scaffold>().unwrap().path();
+        let routines = Routines::compress(&path).unwrap();
+        assert_eq!(expand(&routines), path);
+        assert!(routines.main_routine().len() <= MAX_ROUTINE_LEN);
+        assert!(routines
+            .funcs
+            .iter()
+            .all(|func| join(func).len() <= MAX_ROUTINE_LEN));
+        assert!(Routines::compress(&[Segment(Turn::Left, 1000000000000000000)]).is_none());
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let scaffold = "..#..........\n\
+                        ..#..........\n\
+                        #######...###\n\
+                        #.#...#...#.#\n\
+                        #############\n\
+                        ..#...#...#..\n\
+                        ..#####...^.."
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&scaffold), 76);
     }
 }
@@ -1,24 +1,328 @@
-fn part1() -> u64 {
-    0
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+const NUM_KEYS: usize = 26;
+const MAX_ROBOTS: usize = 4;
+
+pub type Pos = (usize, usize);
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+struct Edge {
+    to: usize,
+    dist: u64,
+    // Doors on the way that must already be unlocked.
+    doors: u32,
+    // Keys on the way that are picked up in passing.
+    keys: u32,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Maze {
+    grid: Vec<Vec<u8>>,
+}
+
+fn key_bit(c: u8) -> u32 {
+    1 << (c.to_ascii_lowercase() - b'a')
+}
+
+impl FromStr for Maze {
+    type Err = AocError;
+
+    fn from_str(maze: &str) -> Result<Self, Self::Err> {
+        let grid = maze
+            .lines()
+            .map(|line| line.trim().bytes().collect::<Vec<_>>())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        for line in maze.lines().map(str::trim) {
+            if let Some((idx, c)) = line
+                .char_indices()
+                .find(|(_, c)| !matches!(c, '#' | '.' | '@' | 'a'..='z' | 'A'..='Z'))
+            {
+                let tile = &line[idx..idx + c.len_utf8()];
+                return Err(AocError::at(maze, tile, format!("Invalid tile {}", c)));
+            }
+        }
+        Ok(Self { grid })
+    }
+}
+
+impl Maze {
+    pub fn get(&self, (x, y): Pos) -> u8 {
+        self.grid
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or(b'#')
+    }
+
+    fn find(&self, pred: impl Fn(u8) -> bool) -> Vec<(Pos, u8)> {
+        self.grid
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| ((x, y), *c)))
+            .filter(|(_, c)| pred(*c))
+            .collect()
+    }
+
+    fn neighbors((x, y): Pos) -> Vec<Pos> {
+        let mut neighbors = vec![(x + 1, y), (x, y + 1)];
+        neighbors.extend(x.checked_sub(1).map(|x| (x, y)));
+        neighbors.extend(y.checked_sub(1).map(|y| (x, y)));
+        neighbors
+    }
+
+    // Replace the area around a single robot with four robots separated by
+    // walls.
+    pub fn split(&self) -> Result<Self, AocError> {
+        let starts = self.find(|c| c == b'@');
+        if starts.len() == MAX_ROBOTS {
+            return Ok(self.clone());
+        }
+        let (x, y) = match starts[..] {
+            [(pos, _)] if pos.0 > 0 && pos.1 > 0 => pos,
+            _ => {
+                return Err(AocError::NoSolution(
+                    "Expected a single robot to split".into(),
+                ))
+            }
+        };
+        let mut maze = self.clone();
+        for (dy, row) in ["@#@", "###", "@#@"].iter().enumerate() {
+            for (dx, c) in row.bytes().enumerate() {
+                let tile = maze
+                    .grid
+                    .get_mut(y + dy - 1)
+                    .and_then(|row| row.get_mut(x + dx - 1))
+                    .ok_or_else(|| AocError::NoSolution("Robot too close to the edge".into()))?;
+                *tile = c;
+            }
+        }
+        Ok(maze)
+    }
+
+    fn edges_from(&self, start: Pos) -> Vec<Edge> {
+        let mut edges = vec![];
+        let mut seen = HashSet::new();
+        let mut todo = VecDeque::new();
+        seen.insert(start);
+        todo.push_back((start, 0, 0, 0));
+        while let Some((pos, dist, doors, keys)) = todo.pop_front() {
+            let c = self.get(pos);
+            let (doors, keys) = match c {
+                b'a'..=b'z' if pos != start => {
+                    edges.push(Edge {
+                        to: (c - b'a') as usize,
+                        dist,
+                        doors,
+                        keys,
+                    });
+                    (doors, keys | key_bit(c))
+                }
+                b'A'..=b'Z' => (doors | key_bit(c), keys),
+                _ => (doors, keys),
+            };
+            for next in Self::neighbors(pos) {
+                if self.get(next) != b'#' && seen.insert(next) {
+                    todo.push_back((next, dist + 1, doors, keys));
+                }
+            }
+        }
+        edges
+    }
+
+    // Dijkstra over (robot positions, collected keys), where robots only ever
+    // stop on keys so the state space stays small.
+    pub fn shortest(&self) -> Result<u64, AocError> {
+        let starts = self.find(|c| c == b'@');
+        if starts.is_empty() || starts.len() > MAX_ROBOTS {
+            return Err(AocError::NoSolution(format!(
+                "Invalid number of robots {}",
+                starts.len()
+            )));
+        }
+        let keys = self.find(|c| c.is_ascii_lowercase());
+        let all_keys = keys.iter().fold(0, |all, (_, c)| all | key_bit(*c));
+
+        let mut graph = vec![vec![]; NUM_KEYS + MAX_ROBOTS];
+        for (pos, c) in keys {
+            graph[(c - b'a') as usize] = self.edges_from(pos);
+        }
+        let mut robots = [0; MAX_ROBOTS];
+        for (i, (pos, _)) in starts.iter().enumerate() {
+            robots[i] = NUM_KEYS + i;
+            graph[NUM_KEYS + i] = self.edges_from(*pos);
+        }
+
+        let mut dists = HashMap::new();
+        let mut todo = BinaryHeap::new();
+        dists.insert((robots, 0), 0);
+        todo.push(Reverse((0, robots, 0)));
+        while let Some(Reverse((dist, robots, held))) = todo.pop() {
+            if held == all_keys {
+                return Ok(dist);
+            }
+            if dists.get(&(robots, held)).is_some_and(|d| *d < dist) {
+                continue;
+            }
+            for (i, node) in robots.iter().take(starts.len()).enumerate() {
+                for edge in &graph[*node] {
+                    if held & (1 << edge.to) != 0 || edge.doors & !held != 0 {
+                        continue;
+                    }
+                    let mut next = robots;
+                    next[i] = edge.to;
+                    let next_held = held | edge.keys | (1 << edge.to);
+                    let next_dist = dist + edge.dist;
+                    if dists.get(&(next, next_held)).is_none_or(|d| next_dist < *d) {
+                        dists.insert((next, next_held), next_dist);
+                        todo.push(Reverse((next_dist, next, next_held)));
+                    }
+                }
+            }
+        }
+        Err(AocError::no_solution())
+    }
 }
 
-fn part2() -> u64 {
-    0
+fn part1(maze: &Maze) -> Result<u64, AocError> {
+    maze.shortest()
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p18.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+fn part2(maze: &Maze) -> Result<u64, AocError> {
+    maze.split()?.shortest()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(maze: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(maze)?.into())
+    }
+
+    fn part2(maze: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(maze)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_split() {
+        let maze = "#######\n\
+                    #a.#Cd#\n\
+                    ##...##\n\
+                    ##.@.##\n\
+                    ##...##\n\
+                    #cB#Ab#\n\
+                    #######"
+            .parse::<Maze>()
+            .unwrap();
+        let split = "#######\n\
+                     #a.#Cd#\n\
+                     ##@#@##\n\
+                     #######\n\
+                     ##@#@##\n\
+                     #cB#Ab#\n\
+                     #######"
+            .parse::<Maze>()
+            .unwrap();
+        assert_eq!(maze.split(), Ok(split.clone()));
+        assert_eq!(split.split(), Ok(split));
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let maze = "#########\n\
+                    #b.A.@.a#\n\
+                    #########"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&maze), Ok(8));
+        let maze = "########################\n\
+                    #f.D.E.e.C.b.A.@.a.B.c.#\n\
+                    ######################.#\n\
+                    #d.....................#\n\
+                    ########################"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&maze), Ok(86));
+        let maze = "########################\n\
+                    #...............b.C.D.f#\n\
+                    #.######################\n\
+                    #.....@.a.B.c.d.A.e.F.g#\n\
+                    ########################"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&maze), Ok(132));
+        let maze = "#################\n\
+                    #i.G..c...e..H.p#\n\
+                    ########.########\n\
+                    #j.A..b...f..D.o#\n\
+                    ########@########\n\
+                    #k.E..a...g..B.n#\n\
+                    ########.########\n\
+                    #l.F..d...h..C.m#\n\
+                    #################"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&maze), Ok(136));
+        let maze = "########################\n\
+                    #@..............ac.GI.b#\n\
+                    ###d#e#f################\n\
+                    ###A#B#C################\n\
+                    ###g#h#i################\n\
+                    ########################"
+            .parse()
+            .unwrap();
+        assert_eq!(part1(&maze), Ok(81));
+    }
+
+    #[test]
+    fn test02() {
+        let maze = "#######\n\
+                    #a.#Cd#\n\
+                    ##...##\n\
+                    ##.@.##\n\
+                    ##...##\n\
+                    #cB#Ab#\n\
+                    #######"
+            .parse()
+            .unwrap();
+        assert_eq!(part2(&maze), Ok(8));
+        let maze = "###############\n\
+                    #d.ABC.#.....a#\n\
+                    ######@#@######\n\
+                    ###############\n\
+                    ######@#@######\n\
+                    #b.....#.....c#\n\
+                    ###############"
+            .parse()
+            .unwrap();
+        assert_eq!(part2(&maze), Ok(24));
+        let maze = "#############\n\
+                    #g#f.D#..h#l#\n\
+                    #F###e#E###.#\n\
+                    #dCba@#@BcIJ#\n\
+                    #############\n\
+                    #nK.L@#@G...#\n\
+                    #M###N#H###.#\n\
+                    #o#m..#i#jk.#\n\
+                    #############"
+            .parse()
+            .unwrap();
+        assert_eq!(part2(&maze), Ok(72));
     }
 }
@@ -1,24 +1,170 @@
-fn part1() -> u64 {
-    0
+use std::fmt;
+
+use crate::error::AocError;
+use crate::intcode::Intcode;
+use crate::solution::{Answer, Solution};
+
+const SCAN_SIZE: u64 = 50;
+const SHIP_SIZE: u64 = 100;
+const MAX_ROWS: u64 = 10_000;
+
+pub trait Beam {
+    fn pulled(&self, x: u64, y: u64) -> Result<bool, AocError>;
+}
+
+impl Beam for Intcode {
+    fn pulled(&self, x: u64, y: u64) -> Result<bool, AocError> {
+        match self
+            .exec()
+            .read_vec(&[x as i64, y as i64])
+            .write_to(vec![])
+            .run_to_out()?
+        {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            Some(out) => Err(AocError::Vm(format!("Invalid drone status {}", out))),
+            None => Err(AocError::Vm("No drone status".into())),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Scan(Vec<Vec<bool>>);
+
+impl Scan {
+    pub fn new<B: Beam>(beam: &B, size: u64) -> Result<Self, AocError> {
+        (0..size)
+            .map(|y| (0..size).map(|x| beam.pulled(x, y)).collect())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.iter().flatten().filter(|pulled| **pulled).count()
+    }
+}
+
+impl fmt::Display for Scan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.0 {
+            for pulled in row {
+                write!(f, "{}", if *pulled { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+// Track the left edge of the beam one row at a time, treating the current row
+// as the bottom of the square. The left edge only ever moves right, so each
+// row costs a couple of queries rather than a full scan.
+pub fn fit_square<B: Beam>(beam: &B, size: u64) -> Result<(u64, u64), AocError> {
+    if size == 0 {
+        return Err(AocError::NoSolution("Square size must be positive".into()));
+    }
+    let mut left = 0;
+    for bottom in size - 1..MAX_ROWS {
+        // Rows close to the emitter can be empty, so give up on a row after
+        // searching as far right as the beam could plausibly reach.
+        let mut x = left;
+        while x <= left + bottom && !beam.pulled(x, bottom)? {
+            x += 1;
+        }
+        if x > left + bottom {
+            continue;
+        }
+        left = x;
+
+        let top = bottom + 1 - size;
+        if beam.pulled(left + size - 1, top)? {
+            return Ok((left, top));
+        }
+    }
+    Err(AocError::no_solution())
+}
+
+fn part1<B: Beam>(beam: &B) -> Result<usize, AocError> {
+    Ok(Scan::new(beam, SCAN_SIZE)?.count())
 }
 
-fn part2() -> u64 {
-    0
+fn part2<B: Beam>(beam: &B, size: u64) -> Result<u64, AocError> {
+    let (x, y) = fit_square(beam, size)?;
+    Ok(x * 10_000 + y)
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p19.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog, SHIP_SIZE)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::Cell;
+
+    // A beam covering y <= x <= 2y that counts how often it is queried.
+    #[derive(Default)]
+    struct Cone {
+        queries: Cell<u64>,
+    }
+
+    impl Beam for Cone {
+        fn pulled(&self, x: u64, y: u64) -> Result<bool, AocError> {
+            self.queries.set(self.queries.get() + 1);
+            Ok(y <= x && x <= 2 * y)
+        }
+    }
+
+    #[test]
+    fn test_scan() {
+        let scan = Scan::new(&Cone::default(), 5).unwrap();
+        assert_eq!(
+            scan.to_string(),
+            "#....\n\
+             .##..\n\
+             ..###\n\
+             ...##\n\
+             ....#\n"
+        );
+        assert_eq!(scan.count(), 9);
+    }
+
+    #[test]
+    fn test_program() {
+        // Reads x and y and reports whether x == y.
+        let prog = Intcode::from(vec![3, 11, 3, 12, 8, 11, 12, 13, 4, 13, 99, 0, 0, 0]);
+        assert_eq!(prog.pulled(3, 3), Ok(true));
+        assert_eq!(prog.pulled(3, 4), Ok(false));
+        assert_eq!(part1(&prog), Ok(SCAN_SIZE as usize));
+    }
+
+    #[test]
+    fn test02() {
+        let cone = Cone::default();
+        assert_eq!(fit_square(&cone, 10), Ok((27, 18)));
+        assert!(cone.queries.get() < 100);
+        assert_eq!(part2(&Cone::default(), 10), Ok(270018));
+    }
 
     #[test]
-    fn test01() {
-        assert_eq!(part1(), 0);
+    fn test_empty_square() {
+        assert!(matches!(
+            fit_square(&Cone::default(), 0),
+            Err(AocError::NoSolution(_))
+        ));
     }
 }
@@ -1,24 +1,282 @@
-fn part1() -> u64 {
-    0
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+const START: &str = "AA";
+const END: &str = "ZZ";
+
+type Pos = (i64, i64);
+
+fn neighbors((x, y): Pos) -> [Pos; 4] {
+    [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Maze {
+    open: HashSet<Pos>,
+    // Each portal tile maps to the tile it leads to and the change in level
+    // when taking it (+1 for inner portals, -1 for outer ones).
+    portals: HashMap<Pos, (Pos, i64)>,
+    start: Pos,
+    end: Pos,
+}
+
+fn parse_err((x, y): Pos, msg: String) -> AocError {
+    AocError::Parse {
+        line: y as usize + 1,
+        column: x as usize + 1,
+        msg,
+    }
+}
+
+impl FromStr for Maze {
+    type Err = AocError;
+
+    fn from_str(maze: &str) -> Result<Self, Self::Err> {
+        let grid = maze
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(move |(x, c)| ((x as i64, y as i64), c))
+            })
+            .collect::<HashMap<_, _>>();
+        let get = |pos: Pos| grid.get(&pos).copied().unwrap_or(' ');
+
+        let open = grid
+            .iter()
+            .filter(|(_, c)| **c == '.')
+            .map(|(pos, _)| *pos)
+            .collect::<HashSet<_>>();
+        let ring = grid.iter().filter(|(_, c)| matches!(c, '#' | '.'));
+        let xmin = ring.clone().map(|((x, _), _)| *x).min().unwrap_or(0);
+        let xmax = ring.clone().map(|((x, _), _)| *x).max().unwrap_or(0);
+        let ymin = ring.clone().map(|((_, y), _)| *y).min().unwrap_or(0);
+        let ymax = ring.map(|((_, y), _)| *y).max().unwrap_or(0);
+
+        // Labels read left to right or top to bottom, with the portal tile on
+        // either end.
+        let mut labels = HashMap::<String, Vec<Pos>>::new();
+        for (&(x, y), &c1) in grid.iter().filter(|(_, c)| c.is_ascii_uppercase()) {
+            for (dx, dy) in &[(1, 0), (0, 1)] {
+                let c2 = get((x + dx, y + dy));
+                if !c2.is_ascii_uppercase() {
+                    continue;
+                }
+                let tile = [(x + 2 * dx, y + 2 * dy), (x - dx, y - dy)]
+                    .iter()
+                    .copied()
+                    .find(|pos| open.contains(pos))
+                    .ok_or_else(|| {
+                        parse_err((x, y), format!("Portal {}{} leads nowhere", c1, c2))
+                    })?;
+                labels
+                    .entry(format!("{}{}", c1, c2))
+                    .or_default()
+                    .push(tile);
+            }
+        }
+
+        let endpoint = |label: &str| match labels.get(label).map(Vec::as_slice) {
+            Some([pos]) => Ok(*pos),
+            _ => Err(AocError::at(
+                maze,
+                maze,
+                format!("Expected a single {} tile", label),
+            )),
+        };
+        let start = endpoint(START)?;
+        let end = endpoint(END)?;
+
+        let mut portals = HashMap::new();
+        for (label, tiles) in &labels {
+            if label == START || label == END {
+                continue;
+            }
+            let (p1, p2) = match tiles[..] {
+                [p1, p2] => (p1, p2),
+                _ => return Err(parse_err(tiles[0], format!("Unpaired portal {}", label))),
+            };
+            let outer = |(x, y): Pos| x == xmin || x == xmax || y == ymin || y == ymax;
+            let delta = |pos| if outer(pos) { -1 } else { 1 };
+            portals.insert(p1, (p2, delta(p1)));
+            portals.insert(p2, (p1, delta(p2)));
+        }
+
+        Ok(Self {
+            open,
+            portals,
+            start,
+            end,
+        })
+    }
+}
+
+impl Maze {
+    // Breadth-first search over (tile, level). In the flat maze the level is
+    // always 0. In the recursive one the level is capped at the number of
+    // portals so that a maze without a way out terminates; this is a
+    // heuristic, as nothing rules out a shortest path that goes deeper.
+    pub fn shortest(&self, recursive: bool) -> Option<u64> {
+        let max_level = if recursive {
+            self.portals.len() as i64
+        } else {
+            0
+        };
+        let mut seen = HashSet::new();
+        let mut todo = VecDeque::new();
+        seen.insert((self.start, 0));
+        todo.push_back((self.start, 0, 0));
+        while let Some((pos, level, dist)) = todo.pop_front() {
+            if pos == self.end && level == 0 {
+                return Some(dist);
+            }
+            let mut next = neighbors(pos)
+                .iter()
+                .filter(|p| self.open.contains(p))
+                .map(|p| (*p, level))
+                .collect::<Vec<_>>();
+            if let Some((dest, delta)) = self.portals.get(&pos) {
+                let level = if recursive { level + delta } else { level };
+                if (0..=max_level).contains(&level) {
+                    next.push((*dest, level));
+                }
+            }
+            for state in next {
+                if seen.insert(state) {
+                    todo.push_back((state.0, state.1, dist + 1));
+                }
+            }
+        }
+        None
+    }
+}
+
+fn part1(maze: &Maze) -> Result<u64, AocError> {
+    maze.shortest(false).ok_or_else(AocError::no_solution)
 }
 
-fn part2() -> u64 {
-    0
+fn part2(maze: &Maze) -> Result<u64, AocError> {
+    maze.shortest(true).ok_or_else(AocError::no_solution)
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p20.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Maze;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(maze: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(maze)?.into())
+    }
+
+    fn part2(maze: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(maze)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn example() -> Maze {
+        [
+            "         A           ",
+            "         A           ",
+            "  #######.#########  ",
+            "  #######.........#  ",
+            "  #######.#######.#  ",
+            "  #######.#######.#  ",
+            "  #######.#######.#  ",
+            "  #####  B    ###.#  ",
+            "BC...##  C    ###.#  ",
+            "  ##.##       ###.#  ",
+            "  ##...DE  F  ###.#  ",
+            "  #####    G  ###.#  ",
+            "  #########.#####.#  ",
+            "DE..#######...###.#  ",
+            "  #.#########.###.#  ",
+            "FG..#########.....#  ",
+            "  ###########.#####  ",
+            "             Z       ",
+            "             Z       ",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap()
+    }
+
+    // The larger recursive example, which goes down to level 10.
+    fn larger() -> Maze {
+        [
+            "             Z L X W       C                 ",
+            "             Z P Q B       K                 ",
+            "  ###########.#.#.#.#######.###############  ",
+            "  #...#.......#.#.......#.#.......#.#.#...#  ",
+            "  ###.#.#.#.#.#.#.#.###.#.#.#######.#.#.###  ",
+            "  #.#...#.#.#...#.#.#...#...#...#.#.......#  ",
+            "  #.###.#######.###.###.#.###.###.#.#######  ",
+            "  #...#.......#.#...#...#.............#...#  ",
+            "  #.#########.#######.#.#######.#######.###  ",
+            "  #...#.#    F       R I       Z    #.#.#.#  ",
+            "  #.###.#    D       E C       H    #.#.#.#  ",
+            "  #.#...#                           #...#.#  ",
+            "  #.###.#                           #.###.#  ",
+            "  #.#....OA                       WB..#.#..ZH",
+            "  #.###.#                           #.#.#.#  ",
+            "CJ......#                           #.....#  ",
+            "  #######                           #######  ",
+            "  #.#....CK                         #......IC",
+            "  #.###.#                           #.###.#  ",
+            "  #.....#                           #...#.#  ",
+            "  ###.###                           #.#.#.#  ",
+            "XF....#.#                         RF..#.#.#  ",
+            "  #####.#                           #######  ",
+            "  #......CJ                       NM..#...#  ",
+            "  ###.#.#                           #.###.#  ",
+            "RE....#.#                           #......RF",
+            "  ###.###        X   X       L      #.#.#.#  ",
+            "  #.....#        F   Q       P      #.#.#.#  ",
+            "  ###.###########.###.#######.#########.###  ",
+            "  #.....#...#.....#.......#...#.....#.#...#  ",
+            "  #####.#.###.#######.#######.###.###.#.#.#  ",
+            "  #.......#.......#.#.#.#.#...#...#...#.#.#  ",
+            "  #####.###.#####.#.#.#.#.###.###.#.###.###  ",
+            "  #.......#.....#.#...#...............#...#  ",
+            "  #############.#.#.###.###################  ",
+            "               A O F   N                     ",
+            "               A A D   M                     ",
+        ]
+        .join("\n")
+        .parse()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let maze = example();
+        assert_eq!(maze.start, (9, 2));
+        assert_eq!(maze.end, (13, 16));
+        assert_eq!(maze.portals.len(), 6);
+        assert_eq!(maze.portals[&(9, 6)], ((2, 8), 1));
+        assert_eq!(maze.portals[&(2, 8)], ((9, 6), -1));
+        assert!("  #.#  \n  ###  ".parse::<Maze>().is_err());
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        assert_eq!(part1(&example()), Ok(23));
+    }
+
+    #[test]
+    fn test02() {
+        assert_eq!(part2(&example()), Ok(26));
+        assert_eq!(part2(&larger()), Ok(396));
     }
 }
@@ -1,24 +1,842 @@
-fn part1() -> u64 {
-    0
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::intcode::{ints_to_ascii, Intcode};
+use crate::solution::{Answer, Solution};
+
+const MAX_INSTRS: usize = 15;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub enum Reg {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+    G,
+    H,
+    I,
+    T,
+    J,
+}
+
+impl Reg {
+    const fn writable(self) -> bool {
+        matches!(self, Self::T | Self::J)
+    }
+
+    // How far ahead a sensor register looks, if it is one.
+    const fn distance(self) -> Option<usize> {
+        match self {
+            Self::A => Some(1),
+            Self::B => Some(2),
+            Self::C => Some(3),
+            Self::D => Some(4),
+            Self::E => Some(5),
+            Self::F => Some(6),
+            Self::G => Some(7),
+            Self::H => Some(8),
+            Self::I => Some(9),
+            Self::T | Self::J => None,
+        }
+    }
+}
+
+impl FromStr for Reg {
+    type Err = AocError;
+
+    fn from_str(reg: &str) -> Result<Self, Self::Err> {
+        match reg {
+            "A" => Ok(Self::A),
+            "B" => Ok(Self::B),
+            "C" => Ok(Self::C),
+            "D" => Ok(Self::D),
+            "E" => Ok(Self::E),
+            "F" => Ok(Self::F),
+            "G" => Ok(Self::G),
+            "H" => Ok(Self::H),
+            "I" => Ok(Self::I),
+            "T" => Ok(Self::T),
+            "J" => Ok(Self::J),
+            _ => Err(AocError::at(reg, reg, format!("Invalid register {}", reg))),
+        }
+    }
+}
+
+impl fmt::Display for Reg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub enum Op {
+    And,
+    Or,
+    Not,
+}
+
+impl FromStr for Op {
+    type Err = AocError;
+
+    fn from_str(op: &str) -> Result<Self, Self::Err> {
+        match op {
+            "AND" => Ok(Self::And),
+            "OR" => Ok(Self::Or),
+            "NOT" => Ok(Self::Not),
+            _ => Err(AocError::at(op, op, format!("Invalid instruction {}", op))),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::And => write!(f, "AND"),
+            Self::Or => write!(f, "OR"),
+            Self::Not => write!(f, "NOT"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub struct Instr {
+    pub op: Op,
+    pub src: Reg,
+    pub dst: Reg,
+}
+
+impl Instr {
+    pub const fn new(op: Op, src: Reg, dst: Reg) -> Self {
+        Self { op, src, dst }
+    }
+}
+
+impl FromStr for Instr {
+    type Err = AocError;
+
+    fn from_str(instr: &str) -> Result<Self, Self::Err> {
+        match instr.split_whitespace().collect::<Vec<_>>()[..] {
+            [op, src, dst] => {
+                let within = |err: AocError, part| err.within(instr, part);
+                Ok(Self::new(
+                    op.parse().map_err(|err| within(err, op))?,
+                    src.parse().map_err(|err| within(err, src))?,
+                    dst.parse().map_err(|err| within(err, dst))?,
+                ))
+            }
+            _ => Err(AocError::at(
+                instr,
+                instr,
+                format!("Invalid instruction {}", instr),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Instr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.op, self.src, self.dst)
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Mode {
+    Walk,
+    Run,
+}
+
+impl Mode {
+    const fn range(self) -> usize {
+        match self {
+            Self::Walk => 4,
+            Self::Run => 9,
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Walk => write!(f, "WALK"),
+            Self::Run => write!(f, "RUN"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Script(pub Vec<Instr>);
+
+impl FromStr for Script {
+    type Err = AocError;
+
+    fn from_str(script: &str) -> Result<Self, Self::Err> {
+        script
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.parse()
+                    .map_err(|err: AocError| err.within(script, line))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
 }
 
-fn part2() -> u64 {
-    0
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instr in &self.0 {
+            writeln!(f, "{}", instr)?;
+        }
+        Ok(())
+    }
+}
+
+impl Script {
+    pub fn validate(&self, mode: Mode) -> Result<(), AocError> {
+        if self.0.len() > MAX_INSTRS {
+            return Err(AocError::Vm(format!(
+                "Script has {} instructions but at most {} are allowed",
+                self.0.len(),
+                MAX_INSTRS
+            )));
+        }
+        for instr in &self.0 {
+            if !instr.dst.writable() {
+                return Err(AocError::Vm(format!(
+                    "Cannot write to register {} in {}",
+                    instr.dst, instr
+                )));
+            }
+            if instr.src.distance().is_some_and(|dist| dist > mode.range()) {
+                return Err(AocError::Vm(format!(
+                    "Register {} is not available in {} mode",
+                    instr.src, mode
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The last frame of the animation shown when the droid falls into a hole.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Fall {
+    pub hull: String,
+    pub droid: Option<usize>,
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p21.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+impl FromStr for Fall {
+    type Err = AocError;
+
+    fn from_str(out: &str) -> Result<Self, Self::Err> {
+        let frame = out
+            .split("\n\n")
+            .filter(|frame| frame.contains('#'))
+            .last()
+            .ok_or_else(|| AocError::Vm("No hull in droid output".into()))?;
+        let hull = frame
+            .lines()
+            .find(|line| line.contains('#'))
+            .ok_or_else(|| AocError::Vm("No hull in droid output".into()))?;
+        // A droid standing in the hull row has fallen into a hole.
+        Ok(Self {
+            hull: hull.replace('@', "."),
+            droid: frame.lines().find_map(|line| line.find('@')),
+        })
+    }
+}
+
+impl fmt::Display for Fall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Droid fell at hull")?;
+        writeln!(f, "{}", self.hull)?;
+        if let Some(x) = self.droid {
+            write!(f, "{}^", " ".repeat(x))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Outcome {
+    Damage(i64),
+    Fell(Fall),
+}
+
+fn run_script(prog: &Intcode, script: &Script, mode: Mode) -> Result<Outcome, AocError> {
+    script.validate(mode)?;
+    let out = prog
+        .exec()
+        .read_ascii(&format!("{}{}\n", script, mode))
+        .write_to(vec![])
+        .run()?;
+    match out.last() {
+        Some(damage) if *damage > 127 => Ok(Outcome::Damage(*damage)),
+        _ => Ok(Outcome::Fell(ints_to_ascii(&out).parse()?)),
+    }
+}
+
+pub trait Springdroid {
+    fn survey(&self, script: &Script, mode: Mode) -> Result<Outcome, AocError>;
+}
+
+impl Springdroid for Intcode {
+    fn survey(&self, script: &Script, mode: Mode) -> Result<Outcome, AocError> {
+        run_script(self, script, mode)
+    }
+}
+
+// A sensor reading ground, or a hole if the flag is false.
+type Lit = (Reg, bool);
+
+// A set of sensor patterns to walk on: those whose bits under the mask `care`
+// equal `bits`, with bit `k` set when sensor `k` reads ground.
+type Cube = (usize, usize);
+
+const fn covers((bits, care): Cube, input: usize) -> bool {
+    input & care == bits
+}
+
+// Sets `dst` to a literal, whatever it held before.
+fn load((reg, ground): Lit, dst: Reg) -> Vec<Instr> {
+    let mut instrs = vec![Instr::new(Op::Not, reg, dst)];
+    if ground {
+        instrs.push(Instr::new(Op::Not, dst, dst));
+    }
+    instrs
+}
+
+// Computes a disjunction into `dst`. Literals of both signs need `tmp` for
+// the holes unless there is at most one literal of either sign.
+fn disjunction(lits: &[Lit], dst: Reg, tmp: Option<Reg>) -> Option<Vec<Instr>> {
+    let (grounds, holes): (Vec<Lit>, Vec<Lit>) = lits.iter().partition(|(_, ground)| *ground);
+    // l ∨ g1 ∨ g2 ...
+    let ors = |first: Lit, rest: &[Lit], dst: Reg| {
+        let mut instrs = load(first, dst);
+        instrs.extend(rest.iter().map(|(reg, _)| Instr::new(Op::Or, *reg, dst)));
+        instrs
+    };
+    // l ∨ ¬h1 ∨ ¬h2 ... as ¬(¬l ∧ h1 ∧ h2 ...)
+    let ands = |first: Lit, rest: &[Lit], dst: Reg| {
+        let mut instrs = load((first.0, !first.1), dst);
+        instrs.extend(rest.iter().map(|(reg, _)| Instr::new(Op::And, *reg, dst)));
+        instrs.push(Instr::new(Op::Not, dst, dst));
+        instrs
+    };
+    let mut options = vec![];
+    match (&grounds[..], &holes[..]) {
+        ([], []) => options.push(vec![]),
+        (_, [hole]) => options.push(ors(*hole, &grounds, dst)),
+        ([], [first, rest @ ..]) => options.push(ands(*first, rest, dst)),
+        _ => (),
+    }
+    match (&grounds[..], &holes[..]) {
+        ([ground], _) => options.push(ands(*ground, &holes, dst)),
+        ([first, rest @ ..], []) => options.push(ors(*first, rest, dst)),
+        ([first, rest @ ..], [_, _, ..]) => {
+            if let Some(tmp) = tmp {
+                let mut instrs = disjunction(&holes, tmp, None)?;
+                instrs.extend(ors(*first, rest, dst));
+                instrs.push(Instr::new(Op::Or, tmp, dst));
+                options.push(instrs);
+            }
+        }
+        _ => (),
+    }
+    options.into_iter().min_by_key(Vec::len)
+}
+
+// Compiles a conjunction of clauses, trying each clause first since only the
+// first one can use T as scratch.
+fn conjunction(clauses: &[Vec<Lit>]) -> Option<Script> {
+    // T is false at the start of every step.
+    if clauses.is_empty() {
+        return Some(Script(vec![Instr::new(Op::Not, Reg::T, Reg::J)]));
+    }
+    (0..clauses.len())
+        .filter_map(|first| {
+            let mut instrs = disjunction(&clauses[first], Reg::J, Some(Reg::T))?;
+            for (idx, clause) in clauses.iter().enumerate() {
+                match clause[..] {
+                    _ if idx == first => (),
+                    [(reg, true)] => instrs.push(Instr::new(Op::And, reg, Reg::J)),
+                    _ => {
+                        instrs.extend(disjunction(clause, Reg::T, None)?);
+                        instrs.push(Instr::new(Op::And, Reg::T, Reg::J));
+                    }
+                }
+            }
+            Some(Script(instrs))
+        })
+        .min_by_key(|script| script.0.len())
+}
+
+// Derives the jump condition from the hulls the droid has fallen on: picks a
+// truth table over the sensors that crosses all of them, then compiles the
+// shortest formula in conjunctive normal form that agrees with it, leaving
+// the sensor patterns no hull reaches free. Each hull the resulting script
+// falls on is added until the droid gets across. The result is a working
+// script, but only the shortest for the table picked: another table that also
+// crosses every hull may compile to fewer instructions.
+pub struct Synth<'a> {
+    mode: Mode,
+    sensors: &'a [Reg],
+    hulls: Vec<Vec<bool>>,
+}
+
+impl<'a> Synth<'a> {
+    pub fn new(mode: Mode, sensors: &'a [Reg]) -> Result<Self, AocError> {
+        if sensors.len() > 6 {
+            return Err(AocError::NoSolution(
+                "At most 6 sensors can be searched".into(),
+            ));
+        }
+        if let Some(reg) = sensors
+            .iter()
+            .find(|reg| reg.distance().is_none_or(|dist| dist > mode.range()))
+        {
+            return Err(AocError::NoSolution(format!(
+                "Register {} is not a sensor in {} mode",
+                reg, mode
+            )));
+        }
+        Ok(Self {
+            mode,
+            sensors,
+            hulls: vec![],
+        })
+    }
+
+    // Follows the table across the hull, or returns the first sensor pattern
+    // it has no entry for.
+    fn crosses(&self, table: &[Option<bool>], hull: &[bool]) -> Result<bool, usize> {
+        let ground = |pos: usize| hull.get(pos).copied().unwrap_or(true);
+        let mut pos = 0;
+        while pos < hull.len() {
+            let input = self.sensors.iter().enumerate().fold(0, |input, (k, reg)| {
+                input | (usize::from(ground(pos + reg.distance().unwrap())) << k)
+            });
+            pos += if table[input].ok_or(input)? { 4 } else { 1 };
+            if !ground(pos) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // Fills in the table by backtracking over the patterns met on the way,
+    // walking before jumping.
+    fn fill(&self, table: &mut [Option<bool>]) -> bool {
+        for hull in &self.hulls {
+            match self.crosses(table, hull) {
+                Ok(true) => (),
+                Ok(false) => return false,
+                Err(input) => {
+                    for jump in [false, true].iter() {
+                        table[input] = Some(*jump);
+                        if self.fill(table) {
+                            return true;
+                        }
+                    }
+                    table[input] = None;
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // The maximal cubes that avoid every pattern the table jumps on.
+    fn primes(&self, table: &[Option<bool>]) -> Vec<Cube> {
+        let full = table.len() - 1;
+        let valid =
+            |cube: Cube| (0..table.len()).all(|i| !covers(cube, i) || table[i] != Some(true));
+        let mut primes = (0..=full)
+            .flat_map(|care| {
+                (0..=full)
+                    .filter(move |bits| bits & !care == 0)
+                    .map(move |bits| (bits, care))
+            })
+            .filter(|&cube| valid(cube))
+            .filter(|&(bits, care)| {
+                (0..self.sensors.len())
+                    .filter(|k| care & (1 << k) != 0)
+                    .all(|k| !valid((bits & !(1 << k), care & !(1 << k))))
+            })
+            .collect::<Vec<_>>();
+        primes.sort_by_key(|(_, care)| care.count_ones());
+        primes
+    }
+
+    // The clause of the jump condition that is false exactly on a cube.
+    fn clause(&self, (bits, care): Cube) -> Vec<Lit> {
+        self.sensors
+            .iter()
+            .enumerate()
+            .filter(|(k, _)| care & (1 << k) != 0)
+            .map(|(k, reg)| (*reg, bits & (1 << k) == 0))
+            .collect()
+    }
+
+    // Branch and bound over the primes covering each pattern the table
+    // doesn't jump on, keeping the shortest script for this table.
+    fn cover(
+        &self,
+        walks: &[usize],
+        primes: &[Cube],
+        chosen: &mut Vec<Cube>,
+        best: &mut Option<Script>,
+    ) {
+        let clauses = chosen
+            .iter()
+            .map(|cube| self.clause(*cube))
+            .collect::<Vec<_>>();
+        let script = match conjunction(&clauses) {
+            Some(script) => script,
+            None => return,
+        };
+        if script.0.len() >= best.as_ref().map_or(MAX_INSTRS + 1, |best| best.0.len()) {
+            return;
+        }
+        let input = match walks
+            .iter()
+            .find(|&&i| !chosen.iter().any(|cube| covers(*cube, i)))
+        {
+            Some(input) => *input,
+            None => {
+                *best = Some(script);
+                return;
+            }
+        };
+        for prime in primes.iter().filter(|cube| covers(**cube, input)) {
+            chosen.push(*prime);
+            self.cover(walks, primes, chosen, best);
+            chosen.pop();
+        }
+    }
+
+    fn script(&self) -> Result<Script, AocError> {
+        let mut table = vec![None; 1 << self.sensors.len()];
+        if !self.fill(&mut table) {
+            return Err(AocError::NoSolution(
+                "No jump table crosses every hull".into(),
+            ));
+        }
+        let walks = (0..table.len())
+            .filter(|i| table[*i] == Some(false))
+            .collect::<Vec<_>>();
+        let mut best = None;
+        self.cover(&walks, &self.primes(&table), &mut vec![], &mut best);
+        best.ok_or_else(|| AocError::NoSolution("No script fits the jump table".into()))
+    }
+
+    pub fn search<D: Springdroid>(&mut self, droid: &D) -> Result<(Script, i64), AocError> {
+        loop {
+            let script = self.script()?;
+            match droid.survey(&script, self.mode)? {
+                Outcome::Damage(damage) => return Ok((script, damage)),
+                Outcome::Fell(fall) => {
+                    let hull = fall.hull.chars().map(|c| c == '#').collect();
+                    if self.hulls.contains(&hull) {
+                        return Err(AocError::Vm(format!(
+                            "Script disagrees with the droid\n{}",
+                            fall
+                        )));
+                    }
+                    self.hulls.push(hull);
+                }
+            }
+        }
+    }
+}
+
+fn part1<D: Springdroid>(droid: &D) -> Result<i64, AocError> {
+    let sensors = [Reg::A, Reg::B, Reg::C, Reg::D];
+    Ok(Synth::new(Mode::Walk, &sensors)?.search(droid)?.1)
+}
+
+// Searching all nine sensors is out of reach, so only look at the ones that
+// matter for deciding whether the droid can move again after landing.
+fn part2<D: Springdroid>(droid: &D) -> Result<i64, AocError> {
+    let sensors = [Reg::A, Reg::B, Reg::C, Reg::D, Reg::E, Reg::H];
+    Ok(Synth::new(Mode::Run, &sensors)?.search(droid)?.1)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse() {
+        let script = "NOT A J\nAND D J\n".parse::<Script>().unwrap();
+        assert_eq!(
+            script,
+            Script(vec![
+                Instr::new(Op::Not, Reg::A, Reg::J),
+                Instr::new(Op::And, Reg::D, Reg::J),
+            ])
+        );
+        assert_eq!(script.to_string(), "NOT A J\nAND D J\n");
+        assert!("XOR A J".parse::<Script>().is_err());
+        assert!("NOT A".parse::<Script>().is_err());
+        assert!("NOT K J".parse::<Script>().is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let script = "NOT A J\nAND D J".parse::<Script>().unwrap();
+        assert_eq!(script.validate(Mode::Walk), Ok(()));
+        assert!("NOT A D"
+            .parse::<Script>()
+            .unwrap()
+            .validate(Mode::Walk)
+            .is_err());
+        let script = "NOT E J".parse::<Script>().unwrap();
+        assert!(script.validate(Mode::Walk).is_err());
+        assert_eq!(script.validate(Mode::Run), Ok(()));
+        let script = Script(vec![Instr::new(Op::Not, Reg::A, Reg::J); 16]);
+        assert!(script.validate(Mode::Run).is_err());
+    }
+
+    // Whether the script jumps with the given sensor readings.
+    fn jumps(script: &Script, ground: impl Fn(usize) -> bool) -> bool {
+        let (mut t, mut j) = (false, false);
+        for instr in &script.0 {
+            let src = match instr.src {
+                Reg::T => t,
+                Reg::J => j,
+                reg => ground(reg.distance().unwrap()),
+            };
+            let dst = if instr.dst == Reg::T { &mut t } else { &mut j };
+            *dst = match instr.op {
+                Op::And => src && *dst,
+                Op::Or => src || *dst,
+                Op::Not => !src,
+            };
+        }
+        j
+    }
+
+    // A droid that tries scripts against a fixed set of hulls.
+    struct HullDroid(Vec<String>);
+
+    impl Springdroid for HullDroid {
+        fn survey(&self, script: &Script, mode: Mode) -> Result<Outcome, AocError> {
+            script.validate(mode)?;
+            for hull in &self.0 {
+                let ground = |pos: usize| hull.as_bytes().get(pos).is_none_or(|c| *c == b'#');
+                let mut pos = 0;
+                while pos < hull.len() {
+                    pos += if jumps(script, |dist| ground(pos + dist)) {
+                        4
+                    } else {
+                        1
+                    };
+                    if !ground(pos) {
+                        return Ok(Outcome::Fell(Fall {
+                            hull: hull.clone(),
+                            droid: Some(pos),
+                        }));
+                    }
+                }
+            }
+            Ok(Outcome::Damage(self.0.len() as i64 + 1000))
+        }
+    }
+
+    impl HullDroid {
+        fn new(hulls: &[&str]) -> Self {
+            Self(hulls.iter().map(|hull| hull.to_string()).collect())
+        }
+    }
+
+    const WALK: &str = "NOT A J\n\
+                        NOT B T\n\
+                        OR T J\n\
+                        NOT C T\n\
+                        OR T J\n\
+                        AND D J";
+
+    const RUN: &str = "NOT A J\n\
+                       NOT B T\n\
+                       OR T J\n\
+                       NOT C T\n\
+                       OR T J\n\
+                       AND D J\n\
+                       NOT E T\n\
+                       NOT T T\n\
+                       OR H T\n\
+                       AND T J";
+
+    fn walk_droid() -> HullDroid {
+        HullDroid::new(&[
+            "#####.###########",
+            "#####...#########",
+            "#####..#.########",
+            "#####.#..########",
+        ])
+    }
+
+    fn run_droid() -> HullDroid {
+        HullDroid::new(&[
+            "#####.###########",
+            "#####...#########",
+            "#####..#.########",
+            "#####.#..########",
+            "#####.#.##.#.####",
+            "#####.##.##..####",
+            "#####..####.#.###",
+            "#####...##.##.###",
+        ])
+    }
+
+    #[test]
+    fn test_hull_droid() {
+        let walk = WALK.parse::<Script>().unwrap();
+        let run = RUN.parse::<Script>().unwrap();
+        assert_eq!(
+            walk_droid().survey(&walk, Mode::Walk),
+            Ok(Outcome::Damage(1004))
+        );
+        assert_eq!(
+            run_droid().survey(&run, Mode::Run),
+            Ok(Outcome::Damage(1008))
+        );
+        assert!(matches!(
+            run_droid().survey(&walk, Mode::Run),
+            Ok(Outcome::Fell(_))
+        ));
+    }
+
+    // Every hull with 9 tiles between solid ground that the script crosses.
+    fn crossed_by(script: &str, mode: Mode) -> HullDroid {
+        let script = script.parse::<Script>().unwrap();
+        let hulls = (0..1 << 9)
+            .map(|tiles: u32| {
+                let tiles = (0..9)
+                    .map(|k| if tiles & (1 << k) != 0 { '#' } else { '.' })
+                    .collect::<String>();
+                format!("#####{}###", tiles)
+            })
+            .filter(|hull| {
+                let droid = HullDroid(vec![hull.clone()]);
+                matches!(droid.survey(&script, mode), Ok(Outcome::Damage(_)))
+            })
+            .collect();
+        HullDroid(hulls)
+    }
+
+    #[test]
+    fn test_conjunction() {
+        let clauses = vec![
+            vec![(Reg::A, false), (Reg::B, false), (Reg::C, false)],
+            vec![(Reg::D, true)],
+            vec![(Reg::E, true), (Reg::H, true)],
+        ];
+        let script = conjunction(&clauses).unwrap();
+        let run = RUN.parse::<Script>().unwrap();
+        assert_eq!(script.0.len(), run.0.len());
+        for input in 0..1 << 9 {
+            let ground = |dist: usize| input & (1 << (dist - 1)) != 0;
+            assert_eq!(jumps(&script, ground), jumps(&run, ground));
+        }
+        let mixed = vec![
+            (Reg::A, true),
+            (Reg::B, true),
+            (Reg::C, false),
+            (Reg::D, false),
+        ];
+        assert!(conjunction(&[mixed.clone(), mixed.clone()]).is_none());
+        assert!(conjunction(&[mixed]).is_some());
+        assert_eq!(conjunction(&[vec![]]), Some(Script::default()));
+    }
+
+    #[test]
+    fn test_synth() {
+        let droid = crossed_by(WALK, Mode::Walk);
+        let sensors = [Reg::A, Reg::B, Reg::C, Reg::D];
+        let mut synth = Synth::new(Mode::Walk, &sensors).unwrap();
+        let (script, damage) = synth.search(&droid).unwrap();
+        assert_eq!(damage, droid.0.len() as i64 + 1000);
+        assert!(script.0.len() <= WALK.lines().count());
+        assert!(!synth.hulls.is_empty());
+        assert!(Synth::new(Mode::Walk, &[Reg::E]).is_err());
+        assert!(Synth::new(Mode::Walk, &[Reg::T]).is_err());
+    }
+
+    // These hulls need (¬A ∨ ¬B ∨ ¬C) ∧ D ∧ (E ∨ H), or a formula that
+    // crosses the same hulls, as the walking script falls on some of them.
+    #[test]
+    fn test_synth_run() {
+        let droid = crossed_by(RUN, Mode::Run);
+        let walk = WALK.parse::<Script>().unwrap();
+        assert!(matches!(
+            droid.survey(&walk, Mode::Run),
+            Ok(Outcome::Fell(_))
+        ));
+        let sensors = [Reg::A, Reg::B, Reg::C, Reg::D, Reg::E, Reg::H];
+        let mut synth = Synth::new(Mode::Run, &sensors).unwrap();
+        let (script, damage) = synth.search(&droid).unwrap();
+        assert_eq!(damage, droid.0.len() as i64 + 1000);
+        assert!(script.0.len() <= RUN.lines().count());
+        assert!(script.0.iter().any(|instr| instr.src == Reg::H));
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        let droid = crossed_by(WALK, Mode::Walk);
+        assert_eq!(part1(&droid), Ok(droid.0.len() as i64 + 1000));
+    }
+
+    #[test]
+    fn test02() {
+        let droid = crossed_by(RUN, Mode::Run);
+        assert_eq!(part2(&droid), Ok(droid.0.len() as i64 + 1000));
+    }
+
+    #[test]
+    fn test_fall() {
+        let out = "Input instructions:\n\n\
+                   Walking...\n\n\
+                   Didn't make it across:\n\n\
+                   .................\n\
+                   .................\n\
+                   @................\n\
+                   #####.#..########\n\n\
+                   .................\n\
+                   .................\n\
+                   .................\n\
+                   #####@#..########\n\n";
+        let fall = out.parse::<Fall>().unwrap();
+        assert_eq!(
+            fall,
+            Fall {
+                hull: "#####.#..########".into(),
+                droid: Some(5),
+            }
+        );
+        assert!("Walking...\n".parse::<Fall>().is_err());
     }
 }
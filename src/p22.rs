@@ -1,24 +1,256 @@
-fn part1() -> u64 {
-    0
+use std::str::FromStr;
+
+use crate::error::{self, AocError};
+use crate::solution::{Answer, Solution};
+
+const DECK1: u64 = 10_007;
+const CARD1: u64 = 2019;
+const DECK2: u64 = 119_315_717_514_047;
+const REPEAT2: u64 = 101_741_582_076_661;
+const POS2: u64 = 2020;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Technique {
+    NewStack,
+    Cut(i64),
+    Increment(u64),
 }
 
-fn part2() -> u64 {
-    0
+impl FromStr for Technique {
+    type Err = AocError;
+
+    fn from_str(tech: &str) -> Result<Self, Self::Err> {
+        let bad_int = |n: &str| AocError::at(tech, n, format!("Bad int {}", n));
+        if tech == "deal into new stack" {
+            Ok(Self::NewStack)
+        } else if let Some(n) = tech.strip_prefix("cut ") {
+            n.parse().map(Self::Cut).map_err(|_| bad_int(n))
+        } else if let Some(n) = tech.strip_prefix("deal with increment ") {
+            n.parse().map(Self::Increment).map_err(|_| bad_int(n))
+        } else {
+            Err(AocError::at(
+                tech,
+                tech,
+                format!("Invalid technique {}", tech),
+            ))
+        }
+    }
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p22.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+// The affine map x -> a * x + b (mod size) taking a card's position before a
+// shuffle to its position after. Everything is kept below `size`, so products
+// fit comfortably in 128 bits.
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Shuffle {
+    a: u128,
+    b: u128,
+    size: u128,
+}
+
+impl Shuffle {
+    pub const fn identity(size: u64) -> Self {
+        Self {
+            a: 1,
+            b: 0,
+            size: size as u128,
+        }
+    }
+
+    pub fn new(tech: Technique, size: u64) -> Self {
+        let m = u128::from(size);
+        let (a, b) = match tech {
+            Technique::NewStack => (m - 1, m - 1),
+            Technique::Cut(n) => {
+                let n = i128::from(n).rem_euclid(m as i128) as u128;
+                (1, (m - n) % m)
+            }
+            Technique::Increment(n) => (u128::from(n) % m, 0),
+        };
+        Self { a, b, size: m }
+    }
+
+    pub fn from_techniques(techs: &[Technique], size: u64) -> Self {
+        techs.iter().fold(Self::identity(size), |shuffle, tech| {
+            shuffle.then(Self::new(*tech, size))
+        })
+    }
+
+    // Apply `self` followed by `other`.
+    pub const fn then(self, other: Self) -> Self {
+        Self {
+            a: (other.a * self.a) % self.size,
+            b: (other.a * self.b + other.b) % self.size,
+            size: self.size,
+        }
+    }
+
+    // Repeated squaring, so shuffling n times takes O(log n) compositions.
+    pub fn pow(self, mut n: u64) -> Self {
+        let mut result = Self::identity(self.size as u64);
+        let mut base = self;
+        while n > 0 {
+            if n & 1 == 1 {
+                result = result.then(base);
+            }
+            base = base.then(base);
+            n >>= 1;
+        }
+        result
+    }
+
+    pub fn inverse(self) -> Option<Self> {
+        let a_inv = mod_inverse(self.a, self.size)?;
+        Some(Self {
+            a: a_inv,
+            b: (self.size - (a_inv * self.b) % self.size) % self.size,
+            size: self.size,
+        })
+    }
+
+    pub const fn apply(self, pos: u64) -> u64 {
+        ((self.a * pos as u128 + self.b) % self.size) as u64
+    }
+}
+
+// Extended Euclid's algorithm, tracking only the coefficient of `x`.
+fn mod_inverse(x: u128, m: u128) -> Option<u128> {
+    let (mut r0, mut r1) = (m as i128, x as i128);
+    let (mut t0, mut t1) = (0_i128, 1_i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    if r0 == 1 {
+        Some(t0.rem_euclid(m as i128) as u128)
+    } else {
+        None
+    }
+}
+
+fn part1(techs: &[Technique]) -> u64 {
+    Shuffle::from_techniques(techs, DECK1).apply(CARD1)
+}
+
+fn part2(techs: &[Technique]) -> Result<u64, AocError> {
+    Shuffle::from_techniques(techs, DECK2)
+        .pow(REPEAT2)
+        .inverse()
+        .map(|shuffle| shuffle.apply(POS2))
+        .ok_or_else(|| AocError::NoSolution("Shuffle is not invertible".into()))
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Vec<Technique>;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        error::parse_lines(input)
+    }
+
+    fn part1(techs: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(techs).into())
+    }
+
+    fn part2(techs: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(techs)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn deck(techs: &str, size: u64) -> Vec<u64> {
+        let techs = techs
+            .lines()
+            .map(str::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let inv = Shuffle::from_techniques(&techs, size).inverse().unwrap();
+        (0..size).map(|pos| inv.apply(pos)).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("deal into new stack".parse(), Ok(Technique::NewStack));
+        assert_eq!("cut -4".parse(), Ok(Technique::Cut(-4)));
+        assert_eq!("deal with increment 7".parse(), Ok(Technique::Increment(7)));
+        assert!("cut the deck".parse::<Technique>().is_err());
+        assert!("shuffle".parse::<Technique>().is_err());
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        assert_eq!(
+            deck("deal into new stack", 10),
+            vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0]
+        );
+        assert_eq!(deck("cut 3", 10), vec![3, 4, 5, 6, 7, 8, 9, 0, 1, 2]);
+        assert_eq!(deck("cut -4", 10), vec![6, 7, 8, 9, 0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            deck("deal with increment 3", 10),
+            vec![0, 7, 4, 1, 8, 5, 2, 9, 6, 3]
+        );
+        assert_eq!(
+            deck(
+                "deal with increment 7\n\
+                 deal into new stack\n\
+                 deal into new stack",
+                10
+            ),
+            vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7]
+        );
+        assert_eq!(
+            deck(
+                "cut 6\n\
+                 deal with increment 7\n\
+                 deal into new stack",
+                10
+            ),
+            vec![3, 0, 7, 4, 1, 8, 5, 2, 9, 6]
+        );
+        assert_eq!(
+            deck(
+                "deal with increment 7\n\
+                 deal with increment 9\n\
+                 cut -2",
+                10
+            ),
+            vec![6, 3, 0, 7, 4, 1, 8, 5, 2, 9]
+        );
+        assert_eq!(
+            deck(
+                "deal into new stack\n\
+                 cut -2\n\
+                 deal with increment 7\n\
+                 cut 8\n\
+                 cut -4\n\
+                 deal with increment 7\n\
+                 cut 3\n\
+                 deal with increment 9\n\
+                 deal with increment 3\n\
+                 cut -1",
+                10
+            ),
+            vec![9, 2, 5, 8, 1, 4, 7, 0, 3, 6]
+        );
+    }
+
+    #[test]
+    fn test_pow() {
+        let techs = [
+            Technique::Cut(-3),
+            Technique::Increment(7),
+            Technique::NewStack,
+        ];
+        let shuffle = Shuffle::from_techniques(&techs, 10_007);
+        let repeated = (0..13).fold(Shuffle::identity(10_007), |s, _| s.then(shuffle));
+        assert_eq!(shuffle.pow(13), repeated);
+        let big = Shuffle::from_techniques(&techs, DECK2).pow(REPEAT2);
+        let inv = big.inverse().unwrap();
+        assert_eq!(big.apply(inv.apply(POS2)), POS2);
+        assert_eq!(Shuffle::new(Technique::Increment(2), 10).inverse(), None);
     }
 }
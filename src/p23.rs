@@ -1,24 +1,153 @@
-fn part1() -> u64 {
-    0
+use std::io;
+
+use crate::error::AocError;
+use crate::intcode::{Intcode, IntcodeExec};
+use crate::solution::{Answer, Solution};
+
+pub const NUM_NICS: usize = 50;
+pub const NAT: i64 = 255;
+pub const MAX_ROUNDS: usize = 100_000;
+
+type Nic = IntcodeExec<io::Cursor<Vec<u8>>, Vec<u8>>;
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Event {
+    // A packet was sent to the NAT.
+    Nat(i64, i64),
+    // The network went idle and the NAT woke up computer 0.
+    Wake(i64, i64),
+}
+
+pub struct Network {
+    nics: Vec<Nic>,
+    nat: Option<(i64, i64)>,
+}
+
+impl Network {
+    pub fn new(prog: &Intcode) -> Self {
+        Self {
+            nics: (0..NUM_NICS)
+                .map(|addr| prog.exec().read_vec(&[addr as i64]).write_to(vec![]))
+                .collect(),
+            nat: None,
+        }
+    }
+
+    // Let every computer run until it is waiting for a packet, giving -1 to
+    // those whose queue is empty, and route everything they send.
+    pub fn round(&mut self) -> Result<Vec<Event>, AocError> {
+        let mut events = vec![];
+        let mut idle = true;
+        for addr in 0..self.nics.len() {
+            if self.nics[addr].input_empty() {
+                self.nics[addr].read_next(&[-1]);
+            } else {
+                idle = false;
+            }
+            let outs = self.nics[addr].run_to_input()?;
+            if outs.len() % 3 != 0 {
+                return Err(AocError::Vm(format!("Incomplete packet from {}", addr)));
+            }
+            for packet in outs.chunks(3) {
+                idle = false;
+                let (dest, x, y) = (packet[0], packet[1], packet[2]);
+                if dest == NAT {
+                    self.nat = Some((x, y));
+                    events.push(Event::Nat(x, y));
+                } else {
+                    self.nics
+                        .get_mut(dest as usize)
+                        .ok_or_else(|| AocError::Vm(format!("Invalid address {}", dest)))?
+                        .read_next(&[x, y]);
+                }
+            }
+        }
+
+        if idle {
+            if let Some((x, y)) = self.nat {
+                self.nics[0].read_next(&[x, y]);
+                events.push(Event::Wake(x, y));
+            }
+        }
+        Ok(events)
+    }
+
+    pub fn find_map<T>(&mut self, mut f: impl FnMut(Event) -> Option<T>) -> Result<T, AocError> {
+        for _ in 0..MAX_ROUNDS {
+            if let Some(res) = self.round()?.into_iter().find_map(&mut f) {
+                return Ok(res);
+            }
+        }
+        Err(AocError::no_solution())
+    }
 }
 
-fn part2() -> u64 {
-    0
+fn part1(prog: &Intcode) -> Result<i64, AocError> {
+    Network::new(prog).find_map(|event| match event {
+        Event::Nat(_, y) => Some(y),
+        Event::Wake(_, _) => None,
+    })
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p23.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+fn part2(prog: &Intcode) -> Result<i64, AocError> {
+    let mut last = None;
+    Network::new(prog).find_map(|event| match event {
+        Event::Wake(_, y) if last == Some(y) => Some(y),
+        Event::Wake(_, y) => {
+            last = Some(y);
+            None
+        }
+        Event::Nat(_, _) => None,
+    })
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(prog)?.into())
+    }
+
+    fn part2(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(prog)?.into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // Each computer sends (addr, 10 * addr + 7) to the NAT on boot and then
+    // ignores everything it receives.
+    fn prog() -> Intcode {
+        Intcode::from(vec![
+            3, 100, 104, 255, 4, 100, 1002, 100, 10, 101, 1001, 101, 7, 101, 4, 101, 3, 102, 1105,
+            1, 16,
+        ])
+    }
+
+    #[test]
+    fn test_round() {
+        let mut network = Network::new(&prog());
+        let events = network.round().unwrap();
+        assert_eq!(events.len(), NUM_NICS);
+        assert_eq!(events[1], Event::Nat(1, 17));
+        assert_eq!(network.round(), Ok(vec![Event::Wake(49, 497)]));
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        assert_eq!(part1(&prog()), Ok(7));
+    }
+
+    #[test]
+    fn test02() {
+        assert_eq!(part2(&prog()), Ok(497));
     }
 }
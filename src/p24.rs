@@ -1,24 +1,252 @@
-fn part1() -> u64 {
-    0
+use std::collections::{HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::solution::{Answer, Solution};
+
+const SIZE: usize = 5;
+const CELLS: usize = SIZE * SIZE;
+const CENTER: usize = CELLS / 2;
+const MINUTES: usize = 200;
+
+const fn bit(x: usize, y: usize) -> u32 {
+    1 << (y * SIZE + x)
+}
+
+// Neighbour masks for each cell: on the same level, on the level outside it
+// (which this grid sits in the center of) and on the level inside it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+struct Masks {
+    same: u32,
+    outer: u32,
+    inner: u32,
+}
+
+fn masks(recursive: bool) -> [Masks; CELLS] {
+    let mut masks = [Masks::default(); CELLS];
+    for (i, mask) in masks.iter_mut().enumerate() {
+        let (x, y) = (i % SIZE, i / SIZE);
+        if recursive && i == CENTER {
+            continue;
+        }
+        let dirs: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        for (dx, dy) in dirs.iter() {
+            let nx = x as isize + dx;
+            let ny = y as isize + dy;
+            let off_grid = nx < 0 || ny < 0 || nx >= SIZE as isize || ny >= SIZE as isize;
+            if off_grid {
+                if recursive {
+                    let c = (SIZE / 2) as isize;
+                    mask.outer |= bit((c + dx) as usize, (c + dy) as usize);
+                }
+            } else if recursive && (nx as usize, ny as usize) == (SIZE / 2, SIZE / 2) {
+                // The whole edge of the inner grid facing this cell.
+                for k in 0..SIZE {
+                    mask.inner |= match (dx, dy) {
+                        (1, _) => bit(0, k),
+                        (-1, _) => bit(SIZE - 1, k),
+                        (_, 1) => bit(k, 0),
+                        _ => bit(k, SIZE - 1),
+                    };
+                }
+            } else {
+                mask.same |= bit(nx as usize, ny as usize);
+            }
+        }
+    }
+    masks
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash, Default)]
+pub struct Grid(u32);
+
+impl FromStr for Grid {
+    type Err = AocError;
+
+    fn from_str(grid: &str) -> Result<Self, Self::Err> {
+        let rows = grid.lines().map(str::trim).collect::<Vec<_>>();
+        if rows.len() != SIZE || rows.iter().any(|row| row.len() != SIZE) {
+            return Err(AocError::at(
+                grid,
+                grid,
+                format!("Expected a {0}x{0} grid", SIZE),
+            ));
+        }
+        let mut bugs = 0;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, (idx, c)) in row.char_indices().enumerate() {
+                match c {
+                    '#' => bugs |= bit(x, y),
+                    '.' | '?' => {}
+                    _ => {
+                        let tile = &row[idx..idx + c.len_utf8()];
+                        return Err(AocError::at(grid, tile, format!("Invalid tile {}", c)));
+                    }
+                }
+            }
+        }
+        Ok(Self(bugs))
+    }
+}
+
+impl Grid {
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub const fn biodiversity(self) -> u32 {
+        self.0
+    }
+
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    // One minute on a level of its own.
+    pub fn next(self) -> Self {
+        self.step(Self::default(), Self::default(), &masks(false))
+    }
+
+    // A bug survives with exactly one neighbour, and an empty cell gets
+    // infested with one or two.
+    fn step(self, outer: Self, inner: Self, masks: &[Masks; CELLS]) -> Self {
+        let mut next = 0;
+        for (i, mask) in masks.iter().enumerate() {
+            let n = (self.0 & mask.same).count_ones()
+                + (outer.0 & mask.outer).count_ones()
+                + (inner.0 & mask.inner).count_ones();
+            let bug = self.0 & (1 << i) != 0;
+            if n == 1 || (!bug && n == 2) {
+                next |= 1 << i;
+            }
+        }
+        Self(next)
+    }
+}
+
+// Recursive levels from outermost to innermost, keeping only populated ones.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Levels {
+    grids: VecDeque<Grid>,
+    masks: [Masks; CELLS],
+}
+
+impl Levels {
+    // The center of `grid` holds the next level in, so a bug there is dropped.
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            grids: vec![Grid(grid.0 & !(1 << CENTER))].into(),
+            masks: masks(true),
+        }
+    }
+
+    pub fn step(&mut self) {
+        self.grids.push_front(Grid::default());
+        self.grids.push_back(Grid::default());
+        let get = |i: isize| {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| self.grids.get(i))
+                .copied()
+                .unwrap_or_default()
+        };
+        let next = (0..self.grids.len() as isize)
+            .map(|i| get(i).step(get(i - 1), get(i + 1), &self.masks))
+            .collect::<VecDeque<_>>();
+        self.grids = next;
+        while self.grids.front().is_some_and(|grid| grid.is_empty()) {
+            self.grids.pop_front();
+        }
+        while self.grids.back().is_some_and(|grid| grid.is_empty()) {
+            self.grids.pop_back();
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        self.grids.iter().map(|grid| grid.count()).sum()
+    }
 }
 
-fn part2() -> u64 {
-    0
+fn part1(grid: Grid) -> u32 {
+    let masks = masks(false);
+    let mut seen = HashSet::new();
+    let mut grid = grid;
+    while seen.insert(grid) {
+        grid = grid.step(Grid::default(), Grid::default(), &masks);
+    }
+    grid.biodiversity()
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p24.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+fn part2(grid: Grid, minutes: usize) -> u32 {
+    let mut levels = Levels::new(grid);
+    for _ in 0..minutes {
+        levels.step();
+    }
+    levels.count()
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(*grid).into())
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part2(*grid, MINUTES).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = "....#\n\
+                           #..#.\n\
+                           #..##\n\
+                           ..#..\n\
+                           #....";
+
+    #[test]
+    fn test_step() {
+        let masks = masks(false);
+        let grid = EXAMPLE.parse::<Grid>().unwrap();
+        let next = "#..#.\n\
+                    ####.\n\
+                    ###.#\n\
+                    ##.##\n\
+                    .##.."
+            .parse::<Grid>()
+            .unwrap();
+        assert_eq!(grid.step(Grid::default(), Grid::default(), &masks), next);
+        assert_eq!(grid.next(), next);
+    }
+
+    #[test]
+    fn test_masks() {
+        let masks = masks(true);
+        assert_eq!(masks[CENTER].same, 0);
+        assert_eq!(masks[0].outer, bit(2, 1) | bit(1, 2));
+        assert_eq!(masks[7].same.count_ones(), 3);
+        assert_eq!(masks[7].inner, 0b11111);
+        assert_eq!(masks[18].same.count_ones(), 4);
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        assert_eq!(part1(EXAMPLE.parse().unwrap()), 2129920);
+        assert!("....\n....".parse::<Grid>().is_err());
+    }
+
+    #[test]
+    fn test02() {
+        assert_eq!(part2(EXAMPLE.parse().unwrap(), 10), 99);
     }
 }
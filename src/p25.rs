@@ -1,24 +1,624 @@
-fn part1() -> u64 {
-    0
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+use crate::error::AocError;
+use crate::intcode::{ascii_to_ints, ints_to_ascii, Intcode, IntcodeExec};
+use crate::solution::{Answer, Solution};
+
+const PROMPT: &str = "Command?";
+const CHECKPOINT: &str = "Security Checkpoint";
+const MAX_ITEMS: usize = 16;
+
+// Taking the infinite loop never returns control, so it can't be discovered
+// by trying; the others end or wreck the game and are found on the way.
+const TRAPS: [&str; 1] = ["infinite loop"];
+
+pub type Pos = (i64, i64);
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone, Hash)]
+pub enum Dir {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Dir {
+    pub const fn reverse(self) -> Self {
+        match self {
+            Self::North => Self::South,
+            Self::South => Self::North,
+            Self::West => Self::East,
+            Self::East => Self::West,
+        }
+    }
+
+    pub const fn go(self, (x, y): Pos) -> Pos {
+        match self {
+            Self::North => (x, y - 1),
+            Self::South => (x, y + 1),
+            Self::West => (x - 1, y),
+            Self::East => (x + 1, y),
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::North => "north",
+            Self::South => "south",
+            Self::West => "west",
+            Self::East => "east",
+        }
+    }
+}
+
+impl FromStr for Dir {
+    type Err = AocError;
+
+    fn from_str(dir: &str) -> Result<Self, Self::Err> {
+        match dir {
+            "north" => Ok(Self::North),
+            "south" => Ok(Self::South),
+            "west" => Ok(Self::West),
+            "east" => Ok(Self::East),
+            _ => Err(AocError::Vm(format!("Invalid direction {}", dir))),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Room {
+    pub name: String,
+    pub doors: Vec<Dir>,
+    pub items: Vec<String>,
+}
+
+impl Room {
+    // Parses the last room described in `out`, which is where the droid ended
+    // up if it was thrown out of another one.
+    pub fn parse(out: &str) -> Result<Self, AocError> {
+        let desc = out
+            .rfind("== ")
+            .map(|idx| &out[idx..])
+            .ok_or_else(|| AocError::Vm("No room in output".into()))?;
+        let mut lines = desc.lines();
+        let name = lines
+            .next()
+            .and_then(|line| line.strip_prefix("== ")?.strip_suffix(" =="))
+            .ok_or_else(|| AocError::Vm("Invalid room name".into()))?
+            .to_string();
+
+        let mut doors = vec![];
+        let mut items = vec![];
+        let mut section = "";
+        for line in lines {
+            if let Some(entry) = line.strip_prefix("- ") {
+                match section {
+                    "Doors here lead:" => doors.push(entry.parse()?),
+                    "Items here:" => items.push(entry.to_string()),
+                    _ => return Err(AocError::Vm(format!("Unexpected list entry {}", entry))),
+                }
+            } else if !line.is_empty() {
+                section = line;
+            }
+        }
+        Ok(Self { name, doors, items })
+    }
+}
+
+pub fn password(out: &str) -> Option<u64> {
+    let (_, rest) = out.split_once("typing ")?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+pub trait Console {
+    // Sends a line of input and returns everything printed before the game
+    // asks for the next one.
+    fn send(&mut self, cmd: &str) -> Result<String, AocError>;
+}
+
+impl<O: io::Write> Console for IntcodeExec<io::Cursor<Vec<u8>>, O> {
+    fn send(&mut self, cmd: &str) -> Result<String, AocError> {
+        if !cmd.is_empty() {
+            self.read_next(&ascii_to_ints(&format!("{}\n", cmd)));
+        }
+        self.run_to_input().map(|out| ints_to_ascii(&out))
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct Ship {
+    rooms: HashMap<String, Room>,
+    // Directions from the starting room to each room.
+    paths: HashMap<String, Vec<Dir>>,
+    // The door out of the checkpoint onto the pressure-sensitive floor.
+    floor: Option<Dir>,
+    items: Vec<String>,
+}
+
+impl Ship {
+    // The items that are safe to pick up.
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    // Explores the whole ship with a fresh droid, learning new traps the hard
+    // way and starting over each time one is picked up. Returns the droid back
+    // in the starting room carrying every safe item.
+    pub fn explore<C: Console>(new: impl Fn() -> C) -> Result<(Self, C), AocError> {
+        let mut traps = TRAPS.iter().map(|s| s.to_string()).collect::<HashSet<_>>();
+        loop {
+            let mut console = new();
+            let room = Room::parse(&console.send("")?)?;
+            let mut ship = Self::default();
+            match ship.visit(&mut console, room, &mut vec![], &traps)? {
+                Some(trap) => traps.insert(trap),
+                None => return Ok((ship, console)),
+            };
+        }
+    }
+
+    // Depth-first search that takes every item on the way and returns to the
+    // room it started from, or stops early with the trap item that was taken.
+    fn visit<C: Console>(
+        &mut self,
+        console: &mut C,
+        room: Room,
+        path: &mut Vec<Dir>,
+        traps: &HashSet<String>,
+    ) -> Result<Option<String>, AocError> {
+        for item in room.items.iter().filter(|item| !traps.contains(*item)) {
+            let out = console.send(&format!("take {}", item))?;
+            if !out.contains(PROMPT) || out.contains("can't move") {
+                return Ok(Some(item.clone()));
+            }
+            self.items.push(item.clone());
+        }
+        self.paths.insert(room.name.clone(), path.clone());
+        self.rooms.insert(room.name.clone(), room.clone());
+
+        for &dir in &room.doors {
+            if path.last().map(|d| d.reverse()) == Some(dir) {
+                continue;
+            }
+            let next = Room::parse(&console.send(dir.name())?)?;
+            if next.name == room.name {
+                // Thrown back by the floor.
+                self.floor = Some(dir);
+                continue;
+            }
+            if !self.rooms.contains_key(&next.name) {
+                path.push(dir);
+                let trap = self.visit(console, next, path, traps)?;
+                path.pop();
+                if trap.is_some() {
+                    return Ok(trap);
+                }
+            }
+            console.send(dir.reverse().name())?;
+        }
+        Ok(None)
+    }
+
+    // Walks to the checkpoint and steps onto the floor with every subset of
+    // the items, in Gray code order so each attempt drops or takes only one.
+    pub fn crack<C: Console>(&self, console: &mut C) -> Result<u64, AocError> {
+        let path = self
+            .paths
+            .get(CHECKPOINT)
+            .ok_or_else(|| AocError::NoSolution("No security checkpoint".into()))?;
+        let floor = self
+            .floor
+            .ok_or_else(|| AocError::NoSolution("No pressure-sensitive floor".into()))?;
+        if self.items.len() > MAX_ITEMS {
+            return Err(AocError::NoSolution(format!(
+                "Too many items ({})",
+                self.items.len()
+            )));
+        }
+        for dir in path {
+            console.send(dir.name())?;
+        }
+
+        let all = (1_u32 << self.items.len()) - 1;
+        let mut held = all;
+        for i in 0..=all {
+            let wanted = !(i ^ (i >> 1)) & all;
+            for (bit, item) in self.items.iter().enumerate() {
+                let mask = 1 << bit;
+                if held & mask != wanted & mask {
+                    let verb = if wanted & mask != 0 { "take" } else { "drop" };
+                    console.send(&format!("{} {}", verb, item))?;
+                }
+            }
+            held = wanted;
+            if let Some(password) = password(&console.send(floor.name())?) {
+                return Ok(password);
+            }
+        }
+        Err(AocError::no_solution())
+    }
+}
+
+// Map of the rooms visited so far, each placed next to the room it was
+// entered from. The ship isn't guaranteed to be laid out on a grid, so rooms
+// may end up drawn on top of each other.
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+struct Atlas {
+    rooms: HashMap<String, Pos>,
+    doors: HashSet<(Pos, Dir)>,
+    here: Option<String>,
+}
+
+impl Atlas {
+    fn visit(&mut self, moved: Option<Dir>, room: &Room) {
+        let from = self.here.as_ref().and_then(|name| self.rooms.get(name));
+        let pos = match (self.rooms.get(&room.name), from, moved) {
+            (Some(pos), _, _) => *pos,
+            (None, Some(from), Some(dir)) => dir.go(*from),
+            _ => (0, 0),
+        };
+        self.rooms.insert(room.name.clone(), pos);
+        self.doors.extend(room.doors.iter().map(|dir| (pos, *dir)));
+        self.here = Some(room.name.clone());
+    }
+}
+
+// Rooms are drawn as 'o', with '@' for the current one and '?' for rooms
+// behind doors that haven't been taken yet.
+impl fmt::Display for Atlas {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let here = self.here.as_ref().and_then(|name| self.rooms.get(name));
+        let points = self
+            .rooms
+            .values()
+            .copied()
+            .chain(self.doors.iter().map(|(pos, dir)| dir.go(*pos)))
+            .collect::<Vec<_>>();
+        let xmin = points.iter().map(|(x, _)| *x).min().unwrap_or(0);
+        let xmax = points.iter().map(|(x, _)| *x).max().unwrap_or(0);
+        let ymin = points.iter().map(|(_, y)| *y).min().unwrap_or(0);
+        let ymax = points.iter().map(|(_, y)| *y).max().unwrap_or(0);
+
+        let width = (2 * (xmax - xmin) + 1) as usize;
+        let height = (2 * (ymax - ymin) + 1) as usize;
+        let mut grid = vec![vec![' '; width]; height];
+        let cell = |(x, y): Pos| ((2 * (y - ymin)) as usize, (2 * (x - xmin)) as usize);
+        for &(pos, dir) in &self.doors {
+            let (r, c) = cell(pos);
+            let (r2, c2) = cell(dir.go(pos));
+            grid[(r + r2) / 2][(c + c2) / 2] = if r == r2 { '-' } else { '|' };
+            if grid[r2][c2] == ' ' {
+                grid[r2][c2] = '?';
+            }
+        }
+        for pos in self.rooms.values() {
+            let (r, c) = cell(*pos);
+            grid[r][c] = if Some(pos) == here { '@' } else { 'o' };
+        }
+
+        for row in grid {
+            writeln!(f, "{}", row.iter().collect::<String>().trim_end())?;
+        }
+        if let Some(name) = &self.here {
+            writeln!(f, "@ {}", name)?;
+        }
+        Ok(())
+    }
 }
 
-fn part2() -> u64 {
-    0
+// Hands the game over to the user. On top of the game's own commands there
+// are `history`, `save <path>` to write it out, `replay <path>` to send every
+// line of a saved file, `map` and `quit`.
+fn play_with<C: Console, R: BufRead, W: Write>(
+    console: &mut C,
+    input: R,
+    mut out: W,
+) -> Result<(), AocError> {
+    let mut atlas = Atlas::default();
+    let mut history = vec![];
+    let mut replay = VecDeque::new();
+    let mut lines = input.lines();
+
+    let text = console.send("")?;
+    atlas.visit(None, &Room::parse(&text)?);
+    write!(out, "{}", text)?;
+    loop {
+        let line = match replay.pop_front() {
+            Some(line) => {
+                writeln!(out, "{}", line)?;
+                line
+            }
+            None => match lines.next() {
+                Some(line) => line?,
+                None => return Ok(()),
+            },
+        };
+        let line = line.trim();
+        match line.split_once(' ').unwrap_or((line, "")) {
+            ("", _) => {}
+            ("quit", _) => return Ok(()),
+            ("map", _) => write!(out, "{}", atlas)?,
+            ("history", _) => {
+                for (idx, cmd) in history.iter().enumerate() {
+                    writeln!(out, "{:4} {}", idx + 1, cmd)?;
+                }
+            }
+            ("save", path) => {
+                let moves = history.iter().map(|cmd| format!("{}\n", cmd));
+                if let Err(err) = fs::write(path, moves.collect::<String>()) {
+                    writeln!(out, "Cannot save to {}: {}", path, err)?;
+                }
+            }
+            ("replay", path) => match fs::read_to_string(path) {
+                Ok(moves) => replay.extend(moves.lines().map(String::from)),
+                Err(err) => writeln!(out, "Cannot replay {}: {}", path, err)?,
+            },
+            _ => {
+                let text = console.send(line)?;
+                write!(out, "{}", text)?;
+                history.push(line.to_string());
+                if let Ok(room) = Room::parse(&text) {
+                    atlas.visit(line.parse().ok(), &room);
+                }
+                if !text.contains(PROMPT) {
+                    return Ok(());
+                }
+            }
+        }
+    }
 }
 
-pub fn run() -> Result<String, String> {
-    let _input = include_str!("input/p25.txt");
-    let out1 = part1();
-    let out2 = part2();
-    Ok(format!("{} {}", out1, out2))
+fn part1<C: Console>(new: impl Fn() -> C) -> Result<u64, AocError> {
+    let (ship, mut console) = Ship::explore(new)?;
+    ship.crack(&mut console)
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Input = Intcode;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError> {
+        input.parse()
+    }
+
+    fn part1(prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(part1(|| prog.exec().read_vec(&[]))?.into())
+    }
+
+    fn part2(_prog: &Self::Input) -> Result<Answer, AocError> {
+        Ok(Answer::Empty)
+    }
+}
+
+pub fn play(input: &str) -> Result<(), AocError> {
+    let prog = input.parse::<Intcode>()?;
+    let mut console = prog.exec().read_vec(&[]);
+    play_with(&mut console, io::stdin().lock(), io::stdout())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    // A tiny ship with the same wording as the real game:
+    //
+    //   Kitchen -- Checkpoint -- Floor
+    //      |
+    //   Hull Breach -- Storage
+    type MockRoom = (&'static str, Vec<(Dir, usize)>, Vec<String>);
+
+    struct MockShip {
+        rooms: Vec<MockRoom>,
+        pos: usize,
+        inv: Vec<String>,
+        stuck: bool,
+        halted: bool,
+    }
+
+    const FLOOR: usize = 4;
+
+    fn weight(item: &str) -> u32 {
+        match item {
+            "mug" => 1,
+            "wreath" => 2,
+            "coin" => 4,
+            "ornament" => 8,
+            _ => 100,
+        }
+    }
+
+    impl MockShip {
+        fn new() -> Self {
+            let items = |items: &[&str]| items.iter().map(|s| s.to_string()).collect();
+            Self {
+                rooms: vec![
+                    (
+                        "Hull Breach",
+                        vec![(Dir::North, 1), (Dir::East, 2)],
+                        items(&["mug"]),
+                    ),
+                    (
+                        "Kitchen",
+                        vec![(Dir::South, 0), (Dir::East, 3)],
+                        items(&["molten lava", "wreath"]),
+                    ),
+                    (
+                        "Storage",
+                        vec![(Dir::West, 0)],
+                        items(&["giant electromagnet", "coin"]),
+                    ),
+                    (
+                        CHECKPOINT,
+                        vec![(Dir::West, 1), (Dir::North, FLOOR)],
+                        items(&["infinite loop", "ornament"]),
+                    ),
+                    (
+                        "Pressure-Sensitive Floor",
+                        vec![(Dir::South, 3)],
+                        items(&[]),
+                    ),
+                ],
+                pos: 0,
+                inv: vec![],
+                stuck: false,
+                halted: false,
+            }
+        }
+
+        fn describe(&self, room: usize) -> String {
+            let (name, doors, items) = &self.rooms[room];
+            let mut out = format!("\n\n\n== {} ==\nA room.\n\nDoors here lead:\n", name);
+            for (dir, _) in doors {
+                out += &format!("- {}\n", dir.name());
+            }
+            if !items.is_empty() {
+                out += "\nItems here:\n";
+                for item in items {
+                    out += &format!("- {}\n", item);
+                }
+            }
+            out
+        }
+    }
+
+    impl Console for MockShip {
+        fn send(&mut self, cmd: &str) -> Result<String, AocError> {
+            if self.halted {
+                return Err(AocError::Vm("Game over".into()));
+            }
+            let mut out = if cmd.is_empty() {
+                self.describe(self.pos)
+            } else if let Some(item) = cmd.strip_prefix("take ") {
+                let items = &mut self.rooms[self.pos].2;
+                let idx = items
+                    .iter()
+                    .position(|i| i == item)
+                    .ok_or_else(|| AocError::Vm("No such item".into()))?;
+                let item = items.remove(idx);
+                match item.as_str() {
+                    "infinite loop" => return Err(AocError::Vm("Looped forever".into())),
+                    "molten lava" => {
+                        self.halted = true;
+                        return Ok("\nYou melt!\n".into());
+                    }
+                    "giant electromagnet" => self.stuck = true,
+                    _ => {}
+                }
+                self.inv.push(item);
+                if self.stuck {
+                    "\nThe giant electromagnet is stuck to you.  You can't move!!\n".into()
+                } else {
+                    format!("\nYou take the {}.\n", self.inv.last().unwrap())
+                }
+            } else if let Some(item) = cmd.strip_prefix("drop ") {
+                let idx = self
+                    .inv
+                    .iter()
+                    .position(|i| i == item)
+                    .ok_or_else(|| AocError::Vm("Not carrying item".into()))?;
+                let item = self.inv.remove(idx);
+                let out = format!("\nYou drop the {}.\n", item);
+                self.rooms[self.pos].2.push(item);
+                out
+            } else {
+                let dir = cmd.parse::<Dir>()?;
+                let &(_, next) = self.rooms[self.pos]
+                    .1
+                    .iter()
+                    .find(|(d, _)| *d == dir)
+                    .ok_or_else(|| AocError::Vm("You can't go that way.".into()))?;
+                if next == FLOOR {
+                    let weight = self.inv.iter().map(|i| weight(i)).sum::<u32>();
+                    let floor = self.describe(FLOOR);
+                    if weight == 9 {
+                        self.halted = true;
+                        return Ok(format!(
+                            "{}\nYou should be able to get in by typing 2424308736 on the keypad.\n",
+                            floor
+                        ));
+                    }
+                    let verdict = if weight > 9 { "lighter" } else { "heavier" };
+                    format!(
+                        "{}\nAlert! Droids on this ship are {} than the detected value!{}",
+                        floor,
+                        verdict,
+                        self.describe(self.pos)
+                    )
+                } else {
+                    self.pos = next;
+                    self.describe(next)
+                }
+            };
+            out += &format!("\n{}\n", PROMPT);
+            Ok(out)
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let ship = MockShip::new();
+        let room = Room::parse(&ship.describe(1)).unwrap();
+        assert_eq!(room.name, "Kitchen");
+        assert_eq!(room.doors, vec![Dir::South, Dir::East]);
+        assert_eq!(room.items, vec!["molten lava", "wreath"]);
+        let room = Room::parse(&ship.describe(FLOOR)).unwrap();
+        assert!(room.items.is_empty());
+        assert!(Room::parse("Command?").is_err());
+        assert_eq!(password("by typing 42 on the keypad"), Some(42));
+        assert_eq!(password("Alert!"), None);
+    }
+
+    #[test]
+    fn test_explore() {
+        let (ship, console) = Ship::explore(MockShip::new).unwrap();
+        assert_eq!(ship.rooms.len(), 4);
+        assert_eq!(ship.paths[CHECKPOINT], vec![Dir::North, Dir::East]);
+        assert_eq!(ship.floor, Some(Dir::North));
+        let mut items = ship.items.clone();
+        items.sort();
+        assert_eq!(items, vec!["coin", "mug", "ornament", "wreath"]);
+        assert_eq!(console.pos, 0);
+    }
+
     #[test]
     fn test01() {
-        assert_eq!(part1(), 0);
+        assert_eq!(part1(MockShip::new), Ok(2424308736));
+    }
+
+    #[test]
+    fn test_play() {
+        let path = std::env::temp_dir().join(format!("p25-moves-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        let input = format!(
+            "north\ntake wreath\nsave {}\ndrop wreath\nsouth\nreplay {}\nhistory\nmap\nquit\nsouth\n",
+            path, path
+        );
+        let mut ship = MockShip::new();
+        let mut out = vec![];
+        play_with(&mut ship, input.as_bytes(), &mut out).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("You take the wreath."));
+        assert!(out.contains("   5 north\n   6 take wreath\n"));
+        assert!(out.ends_with("@-?\n|\no-?\n@ Kitchen\n"));
+        assert_eq!(ship.pos, 1);
+    }
+
+    #[test]
+    fn test_play_missing_file() {
+        let input = "replay /nonexistent/p25-moves\nnorth\nquit\n";
+        let mut ship = MockShip::new();
+        let mut out = vec![];
+        play_with(&mut ship, input.as_bytes(), &mut out).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Cannot replay /nonexistent/p25-moves: "));
+        assert_eq!(ship.pos, 1);
     }
 }
@@ -0,0 +1,341 @@
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::answers::{Answers, Check};
+#[cfg(feature = "bench")]
+use crate::bench;
+use crate::cli::{Args, Command, Format};
+use crate::error::AocError;
+#[cfg(any(feature = "async", feature = "intcode-debug"))]
+use crate::intcode::Intcode;
+use crate::json::Json;
+#[cfg(feature = "async")]
+use crate::network;
+use crate::solution::{Answer, Day, Part};
+use crate::{days, input, p25};
+
+pub const EXIT_MISMATCH: u8 = 1;
+pub const EXIT_USAGE: u8 = 2;
+
+pub struct Outcome {
+    pub day: u32,
+    pub part: Part,
+    pub answer: Result<Answer, AocError>,
+    pub elapsed: Duration,
+}
+
+// A failed part with its error, or `None` for a wrong answer. Failures of a
+// whole day have no part.
+pub struct Failure {
+    pub day: u32,
+    pub part: Option<Part>,
+    pub err: Option<AocError>,
+}
+
+impl Failure {
+    pub fn new(day: u32, part: Option<Part>, err: AocError) -> Self {
+        Self {
+            day,
+            part,
+            err: Some(err),
+        }
+    }
+}
+
+// Parses the day's input once and times each part separately; a missing
+// input or parse error fails every part.
+pub fn run_day(day: &Day, input: Result<String, AocError>, parts: &[Part]) -> Vec<Outcome> {
+    let input = input.and_then(|input| day.parse(&input));
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match &input {
+                Ok(input) => day.solve(input.as_ref(), part),
+                Err(err) => Err(err.clone()),
+            };
+            Outcome {
+                day: day.day,
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+// Multi-line answers (unread images) go below their row.
+fn print_row(cols: &str, answer: &str) {
+    if answer.contains('\n') {
+        println!("{}", cols);
+        for line in answer.lines() {
+            println!("{}", line);
+        }
+    } else {
+        println!("{}  {}", cols, answer);
+    }
+}
+
+// The art of images whose letters were read, which is otherwise not shown.
+fn print_art(answer: &Result<Answer, AocError>) {
+    if let Ok(answer) = answer {
+        match answer.art() {
+            Some(art) if art != answer.to_string() => println!("{}", art),
+            _ => (),
+        }
+    }
+}
+
+fn print_table(outcomes: &[Outcome], show_art: bool) {
+    println!("{:>3}  {:>4}  {:>10}  Answer", "Day", "Part", "Time");
+    for outcome in outcomes {
+        let answer = match &outcome.answer {
+            Ok(answer) => answer.to_string(),
+            Err(err) => format!("error: {}", err),
+        };
+        let elapsed = format!("{:.1?}", outcome.elapsed);
+        let cols = format!("{:>3}  {:>4}  {:>10}", outcome.day, outcome.part, elapsed);
+        print_row(&cols, &answer);
+        if show_art {
+            print_art(&outcome.answer);
+        }
+    }
+}
+
+// One object per part, in the order they ran.
+fn print_json(outcomes: &[Outcome]) {
+    let outcomes = outcomes
+        .iter()
+        .map(|outcome| {
+            let (answer, error) = match &outcome.answer {
+                Ok(answer) => (Json::from(answer), Json::Null),
+                Err(err) => (Json::Null, Json::Str(err.to_string())),
+            };
+            let part = match outcome.part {
+                Part::One => 1,
+                Part::Two => 2,
+            };
+            Json::Obj(vec![
+                ("day", Json::Num(outcome.day.into())),
+                ("part", Json::Num(part)),
+                ("answer", answer),
+                (
+                    "elapsed_ms",
+                    Json::Float(outcome.elapsed.as_secs_f64() * 1000.0),
+                ),
+                ("error", error),
+            ])
+        })
+        .collect();
+    println!("{}", Json::Arr(outcomes));
+}
+
+fn read_answers(path: &str, record: bool) -> Result<Answers, AocError> {
+    match fs::read_to_string(path) {
+        Ok(answers) => answers.parse().map_err(|err| match err {
+            AocError::Parse { line, column, msg } => AocError::Parse {
+                line,
+                column,
+                msg: format!("{} in {}", msg, path),
+            },
+            err => err,
+        }),
+        Err(err) if record && err.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+        Err(err) => Err(AocError::Io(format!(
+            "Cannot read answers {}: {}",
+            path, err
+        ))),
+    }
+}
+
+// Compares every answer with the recorded one, or records them all. Days
+// that failed to run keep their previous answers.
+fn verify(args: &Args, outcomes: &[Outcome]) -> Result<Vec<Failure>, AocError> {
+    let mut answers = read_answers(&args.answers, args.record)?;
+    let mut failed = vec![];
+    println!("{:>3}  {:>4}  {:<8}  Answer", "Day", "Part", "Status");
+    for outcome in outcomes {
+        let (status, answer) = match &outcome.answer {
+            Err(err) => ("error", err.to_string()),
+            Ok(answer) if args.record => {
+                answers.record(outcome.day, outcome.part, answer.clone());
+                ("recorded", answer.to_string())
+            }
+            Ok(answer) => match answers.check(outcome.day, outcome.part, answer) {
+                Check::Match => ("ok", answer.to_string()),
+                Check::Missing => ("missing", answer.to_string()),
+                Check::Mismatch(expected) => {
+                    let (answer, expected) = (answer.to_string(), expected.to_string());
+                    let sep = if answer.contains('\n') || expected.contains('\n') {
+                        "\nexpected:\n"
+                    } else {
+                        " expected "
+                    };
+                    ("MISMATCH", format!("{}{}{}", answer, sep, expected))
+                }
+            },
+        };
+        if status == "error" || status == "MISMATCH" {
+            failed.push(Failure {
+                day: outcome.day,
+                part: Some(outcome.part),
+                err: outcome.answer.as_ref().err().cloned(),
+            });
+        }
+        let cols = format!("{:>3}  {:>4}  {:<8}", outcome.day, outcome.part, status);
+        print_row(&cols, &answer);
+        if args.show_art {
+            print_art(&outcome.answer);
+        }
+    }
+    if args.record {
+        fs::write(&args.answers, answers.to_string()).map_err(|err| {
+            AocError::Io(format!("Cannot write answers {}: {}", args.answers, err))
+        })?;
+    }
+    Ok(failed)
+}
+
+// Benchmarks every selected day, writing the results file even when some
+// days fail.
+#[cfg(feature = "bench")]
+fn bench(args: &Args, days: &[Day]) -> Result<Vec<Failure>, AocError> {
+    let mut rows = vec![];
+    let mut failed = vec![];
+    println!(
+        "{:>3}  {:>5}  {:>4}  {:>10}  {:>10}  {:>10}",
+        "Day", "Stage", "Runs", "Min", "Median", "Mean"
+    );
+    for day in days.iter().filter(|day| args.days.contains(&day.day)) {
+        let res = input::read(&args.input_path(day.day))
+            .and_then(|input| bench::bench_day(day, &input, &args.parts, args.runs));
+        match res {
+            Ok(day_rows) => {
+                for row in &day_rows {
+                    println!(
+                        "{:>3}  {:>5}  {:>4}  {:>10}  {:>10}  {:>10}",
+                        row.day,
+                        row.stage,
+                        row.stats.runs,
+                        format!("{:.1?}", row.stats.min),
+                        format!("{:.1?}", row.stats.median),
+                        format!("{:.1?}", row.stats.mean)
+                    );
+                }
+                rows.extend(day_rows);
+            }
+            Err(err) => {
+                println!("{:>3}  error: {}", day.day, err);
+                failed.push(Failure::new(day.day, None, err));
+            }
+        }
+    }
+    fs::write(&args.results, bench::to_csv(&rows))
+        .map_err(|err| AocError::Io(format!("Cannot write results {}: {}", args.results, err)))?;
+    Ok(failed)
+}
+
+fn tag(day: u32, part: Option<Part>) -> String {
+    match part {
+        Some(part) => format!("day {} part {}", day, part),
+        None => format!("day {}", day),
+    }
+}
+
+// Prints a diagnostic tagged with its day for every failure, and returns the
+// exit code of the first one.
+pub fn report(failures: &[Failure]) -> u8 {
+    let mut rest = failures;
+    while let Some(first) = rest.first() {
+        let len = rest.iter().take_while(|f| f.day == first.day).count();
+        let (day, others) = rest.split_at(len);
+        rest = others;
+        // A missing or invalid input fails every part the same way.
+        match &first.err {
+            Some(err) if len > 1 && day.iter().all(|f| f.err == first.err) => {
+                eprintln!("{}: {}", tag(first.day, None), err);
+                continue;
+            }
+            _ => (),
+        }
+        for failure in day {
+            match &failure.err {
+                Some(err) => eprintln!("{}: {}", tag(failure.day, failure.part), err),
+                None => eprintln!("{}: wrong answer", tag(failure.day, failure.part)),
+            }
+        }
+    }
+    failures.first().map_or(0, |failure| {
+        failure
+            .err
+            .as_ref()
+            .map_or(EXIT_MISMATCH, AocError::exit_code)
+    })
+}
+
+// Runs the command, printing its results on stdout. Failed days are returned
+// rather than stopping the run; only errors that affect every day are.
+pub fn run(args: &Args) -> Result<Vec<Failure>, AocError> {
+    if args.command == Command::Play {
+        let res = input::read(&args.input_path(25)).and_then(|input| p25::play(&input));
+        return Ok(res
+            .err()
+            .map(|err| Failure::new(25, None, err))
+            .into_iter()
+            .collect());
+    }
+    #[cfg(feature = "async")]
+    if args.command == Command::Network {
+        match input::read(&args.input_path(23))
+            .and_then(|input| input.parse::<Intcode>())
+            .and_then(|prog| network::solve(&prog))
+        {
+            Ok((first, repeated)) => {
+                println!("First packet to the NAT: y = {}", first);
+                println!("First repeated wake-up:  y = {}", repeated);
+            }
+            Err(err) => return Ok(vec![Failure::new(23, None, err)]),
+        }
+        return Ok(vec![]);
+    }
+    #[cfg(feature = "intcode-debug")]
+    if args.command == Command::Disasm {
+        let day = args.days[0];
+        match input::read(&args.input_path(day)).and_then(|input| input.parse::<Intcode>()) {
+            Ok(prog) => println!("{}", prog.disassemble()),
+            Err(err) => return Ok(vec![Failure::new(day, None, err)]),
+        }
+        return Ok(vec![]);
+    }
+
+    let days = days();
+    #[cfg(feature = "bench")]
+    if args.command == Command::Bench {
+        return bench(args, &days);
+    }
+
+    let start = Instant::now();
+    let outcomes = days
+        .iter()
+        .filter(|day| args.days.contains(&day.day))
+        .flat_map(|day| run_day(day, input::read(&args.input_path(day.day)), &args.parts))
+        .collect::<Vec<_>>();
+
+    if args.command == Command::Verify {
+        return verify(args, &outcomes);
+    }
+    if args.format == Format::Json {
+        print_json(&outcomes);
+    } else {
+        print_table(&outcomes, args.show_art);
+        println!("Total {:.1?}", start.elapsed());
+    }
+    Ok(outcomes
+        .into_iter()
+        .filter_map(|outcome| {
+            let err = outcome.answer.err()?;
+            Some(Failure::new(outcome.day, Some(outcome.part), err))
+        })
+        .collect())
+}
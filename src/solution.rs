@@ -0,0 +1,212 @@
+use std::any::Any;
+use std::fmt;
+
+use crate::error::AocError;
+use crate::ocr;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => f.pad("1"),
+            Self::Two => f.pad("2"),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum Answer {
+    Num(i128),
+    Text(String),
+    // Pixel rows, lit or not.
+    Image(Vec<Vec<bool>>),
+    // Day 25 only has one puzzle.
+    Empty,
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Self::Num(n.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Self::Num(n.into())
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Self {
+        Self::Num(n.into())
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Self::Num(n as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Self::Num(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Self::Text(s)
+    }
+}
+
+impl Answer {
+    // Images drawn with block characters, one line per row.
+    pub fn art(&self) -> Option<String> {
+        match self {
+            Self::Image(rows) => {
+                let rows = rows
+                    .iter()
+                    .map(|row| row.iter().map(|lit| if *lit { '\u{2588}' } else { ' ' }))
+                    .map(|row| row.collect::<String>())
+                    .collect::<Vec<_>>();
+                Some(rows.join("\n"))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Images show the letters they spell, or their art when those can't be read.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Num(n) => write!(f, "{}", n),
+            Self::Text(s) => write!(f, "{}", s),
+            Self::Image(rows) => match ocr::read(rows) {
+                Some(letters) => write!(f, "{}", letters),
+                None => write!(f, "{}", self.art().unwrap()),
+            },
+            Self::Empty => write!(f, "-"),
+        }
+    }
+}
+
+pub trait Solution {
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, AocError>;
+    fn part1(input: &Self::Input) -> Result<Answer, AocError>;
+    fn part2(input: &Self::Input) -> Result<Answer, AocError>;
+}
+
+fn parse_any<S: Solution>(input: &str) -> Result<Box<dyn Any>, AocError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn solve_any<S: Solution>(input: &dyn Any, part: Part) -> Result<Answer, AocError> {
+    let input = input
+        .downcast_ref::<S::Input>()
+        .expect("Input was parsed for another day");
+    match part {
+        Part::One => S::part1(input),
+        Part::Two => S::part2(input),
+    }
+}
+
+// A registered day, with the input type of its `Solution` erased so every
+// day fits in the same list.
+pub struct Day {
+    pub day: u32,
+    parse: fn(&str) -> Result<Box<dyn Any>, AocError>,
+    solve: fn(&dyn Any, Part) -> Result<Answer, AocError>,
+}
+
+impl Day {
+    pub fn new<S: Solution>(day: u32) -> Self {
+        Self {
+            day,
+            parse: parse_any::<S>,
+            solve: solve_any::<S>,
+        }
+    }
+
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Any>, AocError> {
+        (self.parse)(input)
+    }
+
+    pub fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer, AocError> {
+        (self.solve)(input, part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(input: &str) -> Result<Self::Input, AocError> {
+            input
+                .split(',')
+                .map(|n| {
+                    n.parse()
+                        .map_err(|_| AocError::at(input, n, format!("Bad int {}", n)))
+                })
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<Answer, AocError> {
+            Ok(input.iter().sum::<i64>().into())
+        }
+
+        fn part2(input: &Self::Input) -> Result<Answer, AocError> {
+            Ok(format!("{:?}", input).into())
+        }
+    }
+
+    #[test]
+    fn test_day() {
+        let day = Day::new::<Sum>(1);
+        let input = day.parse("1,2,3").unwrap();
+        assert_eq!(day.solve(input.as_ref(), Part::One), Ok(Answer::Num(6)));
+        assert_eq!(
+            day.solve(input.as_ref(), Part::Two),
+            Ok(Answer::Text("[1, 2, 3]".into()))
+        );
+        assert_eq!(
+            day.parse("1,x").err(),
+            Some(AocError::Parse {
+                line: 1,
+                column: 3,
+                msg: "Bad int x".into()
+            })
+        );
+        assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(Answer::Empty.to_string(), "-");
+        let image = Answer::Image(vec![vec![true, false], vec![false, true]]);
+        assert_eq!(image.to_string(), "\u{2588} \n \u{2588}");
+        let rows = ["#..#", "#..#", "####", "#..#", "#..#", "#..#"];
+        let image = Answer::Image(
+            rows.iter()
+                .map(|row| row.chars().map(|c| c == '#').collect())
+                .collect(),
+        );
+        assert_eq!(image.to_string(), "H");
+        assert!(image.art().unwrap().starts_with("\u{2588}  \u{2588}\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Input was parsed for another day")]
+    fn test_other_day() {
+        let _ = Day::new::<Sum>(1).solve(&"1,2", Part::One);
+    }
+}
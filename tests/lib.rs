@@ -0,0 +1,106 @@
+use std::{env, fs, process};
+
+use aoc2019::cli::Args;
+use aoc2019::error::AocError;
+use aoc2019::intcode::Intcode;
+use aoc2019::runner;
+use aoc2019::solution::{Answer, Part};
+
+#[test]
+fn test_intcode() {
+    let prog = "3,0,4,0,99".parse::<Intcode>().unwrap();
+    assert_eq!(prog.exec().read_vec(&[42]).run(), Ok(vec![42]));
+    let mut exec = Intcode::from(vec![1, 0, 0, 0, 99]).exec();
+    assert_eq!(exec.run(), Ok(vec![]));
+    assert_eq!(exec[0], 2);
+}
+
+#[test]
+fn test_days() {
+    let day = &aoc2019::days()[0];
+    let input = day.parse("12\n14\n1969\n").unwrap();
+    assert_eq!(day.solve(input.as_ref(), Part::One), Ok(Answer::Num(658)));
+}
+
+#[test]
+fn test_run() {
+    let path = env::temp_dir().join(format!("aoc2019-test-run-{}", process::id()));
+    fs::create_dir_all(&path).unwrap();
+    fs::write(path.join("p01.txt"), "12\n14\n1969\n").unwrap();
+    fs::write(path.join("p04.txt"), "x-1\n").unwrap();
+    let dir = path.to_str().unwrap();
+
+    let args = Args::parse(&["1", "--part", "1", "--input-dir", dir]).unwrap();
+    let failures = runner::run(&args).unwrap();
+    assert!(failures.is_empty());
+    assert_eq!(runner::report(&failures), 0);
+
+    let args = Args::parse(&["4", "--input-dir", dir]).unwrap();
+    let failures = runner::run(&args).unwrap();
+    assert_eq!(failures.len(), 2);
+    assert!(matches!(
+        failures[0].err,
+        Some(AocError::Parse {
+            line: 1,
+            column: 1,
+            ..
+        })
+    ));
+    assert_eq!(runner::report(&failures), 3);
+    fs::remove_dir_all(&path).unwrap();
+}
+
+#[test]
+fn test_day_api() {
+    use aoc2019::{p03, p10, p12, p14, p16, p22, p24};
+
+    let wire1 = "R8,U5,L5,D3".parse::<p03::Wire>().unwrap();
+    let wire2 = "U7,R6,D4,L4".parse::<p03::Wire>().unwrap();
+    assert_eq!(wire1.intersect(&wire2).len(), 2);
+    assert_eq!(wire1.steps_to((6, 5)), Some(15));
+
+    let map = ".#..#\n.....\n#####\n....#\n...##"
+        .parse::<p10::Map>()
+        .unwrap();
+    assert_eq!(map.visible_from(p10::Point::new(3, 4)).len() - 1, 8);
+
+    let mut moons = [
+        "<x=-1, y=0, z=2>",
+        "<x=2, y=-10, z=-7>",
+        "<x=4, y=-8, z=8>",
+        "<x=3, y=5, z=-1>",
+    ]
+    .iter()
+    .map(|pos| p12::Body::new(pos.parse().unwrap()))
+    .collect::<Vec<_>>();
+    for _ in 0..10 {
+        p12::step(&mut moons);
+    }
+    assert_eq!(moons.iter().map(p12::Body::energy).sum::<u64>(), 179);
+
+    let reactions = "10 ORE => 10 A\n1 ORE => 1 B\n7 A, 1 B => 1 C\n7 A, 1 C => 1 D\n7 A, 1 D => 1 E\n7 A, 1 E => 1 FUEL"
+        .parse::<p14::Reactions>()
+        .unwrap();
+    assert_eq!(reactions.ore_for(1), 31);
+
+    let techs = "deal with increment 7\ndeal into new stack\ndeal into new stack"
+        .lines()
+        .map(|tech| tech.parse())
+        .collect::<Result<Vec<p22::Technique>, _>>()
+        .unwrap();
+    let shuffle = p22::Shuffle::from_techniques(&techs, 10);
+    assert_eq!(shuffle.apply(3), 1);
+    assert_eq!(shuffle.inverse().unwrap().apply(1), 3);
+
+    assert_eq!(
+        p16::fft(&[1, 2, 3, 4, 5, 6, 7, 8], 4),
+        [0, 1, 0, 2, 9, 4, 9, 8]
+    );
+
+    let grid = "....#\n#..#.\n#..##\n..#..\n#....".parse().unwrap();
+    let mut levels = p24::Levels::new(grid);
+    for _ in 0..10 {
+        levels.step();
+    }
+    assert_eq!(levels.count(), 99);
+}